@@ -0,0 +1,479 @@
+use std::{cell::Cell, rc::Rc, time::Instant};
+
+use time::Duration;
+
+use super::{BoutEventKind, BoutFormat};
+
+/// Fencing time without a touch before the referee has to call passivity.
+pub const PASSIVITY_LIMIT: Duration = Duration::minutes(1);
+
+/// Where the clock gets the time from, only differences between readings are used.
+pub trait TimeSource {
+    fn now(&self) -> Duration;
+}
+
+impl<S: TimeSource + ?Sized> TimeSource for &S {
+    fn now(&self) -> Duration {
+        (**self).now()
+    }
+}
+
+impl<S: TimeSource + ?Sized> TimeSource for Rc<S> {
+    fn now(&self) -> Duration {
+        (**self).now()
+    }
+}
+
+/// Time since the source was created, from the system's monotonic clock.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemTimeSource {
+    start: Instant,
+}
+
+impl SystemTimeSource {
+    pub fn new() -> Self {
+        SystemTimeSource {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemTimeSource {
+    fn default() -> Self {
+        SystemTimeSource::new()
+    }
+}
+
+impl TimeSource for SystemTimeSource {
+    fn now(&self) -> Duration {
+        Duration::try_from(self.start.elapsed()).unwrap_or(Duration::MAX)
+    }
+}
+
+/// A time source that only moves when told to, for tests and replaying recorded bouts.
+#[derive(Debug, Default)]
+pub struct ManualTimeSource {
+    now: Cell<Duration>,
+}
+
+impl ManualTimeSource {
+    pub fn new() -> Self {
+        ManualTimeSource::default()
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl TimeSource for ManualTimeSource {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockPhase {
+    Period(u8),
+    /// The break after the given period.
+    Break(u8),
+    /// The extra minute after a tie, the fencer with priority wins if it stays tied.
+    Priority,
+    Finished,
+}
+
+/// Keeps the time of a bout, it has to be told about touches and the score.
+///
+/// # Usage
+/// ```
+/// use std::rc::Rc;
+/// use time::Duration;
+/// use fencing_sport_lib::bout::{BoutClock, BoutEventKind, BoutFormat, ClockPhase, ManualTimeSource};
+///
+/// let time = Rc::new(ManualTimeSource::new());
+/// let mut clock = BoutClock::new(BoutFormat::pool(), time.clone());
+/// clock.start();
+/// time.advance(Duration::minutes(3));
+///
+/// assert_eq!(clock.tick((2, 2)), Some(BoutEventKind::TimeExpired));
+/// assert_eq!(clock.phase(), ClockPhase::Priority);
+/// ```
+#[derive(Debug)]
+pub struct BoutClock<S: TimeSource> {
+    source: S,
+    format: BoutFormat,
+    phase: ClockPhase,
+    /// Time run in the current phase before the clock was last started.
+    elapsed: Duration,
+    /// When the clock was started, `None` while it is stopped.
+    since: Option<Duration>,
+    /// Fencing time of the phases that are over.
+    fenced: Duration,
+    /// Fencing time without a touch before the clock was last started.
+    passivity: Duration,
+    score_break_taken: bool,
+    /// Time run in a single period format, like sabre, when the break at a score interrupted it.
+    interrupted: Option<Duration>,
+}
+
+impl<S: TimeSource> BoutClock<S> {
+    pub fn new(format: BoutFormat, source: S) -> Self {
+        BoutClock {
+            source,
+            format,
+            phase: ClockPhase::Period(1),
+            elapsed: Duration::ZERO,
+            since: None,
+            fenced: Duration::ZERO,
+            passivity: Duration::ZERO,
+            score_break_taken: false,
+            interrupted: None,
+        }
+    }
+
+    pub fn phase(&self) -> ClockPhase {
+        self.phase
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.since.is_some()
+    }
+
+    /// Starts the clock on "Fence!", does nothing once the bout is over.
+    pub fn start(&mut self) {
+        if self.phase != ClockPhase::Finished && self.since.is_none() {
+            self.since = Some(self.source.now());
+        }
+    }
+
+    /// Stops the clock on "Halt!".
+    pub fn stop(&mut self) {
+        self.fold();
+        self.since = None;
+    }
+
+    pub fn phase_length(&self) -> Duration {
+        match self.phase {
+            ClockPhase::Period(_) => self.format.period_length(),
+            ClockPhase::Break(_) => self.format.break_length(),
+            ClockPhase::Priority => self.format.priority_length(),
+            ClockPhase::Finished => Duration::ZERO,
+        }
+    }
+
+    /// Time run in the current phase.
+    pub fn elapsed(&self) -> Duration {
+        (self.elapsed + self.running()).min(self.phase_length())
+    }
+
+    /// Time left in the current phase, what the scoring display shows.
+    pub fn remaining(&self) -> Duration {
+        self.phase_length() - self.elapsed()
+    }
+
+    /// Fencing time of the bout without breaks, used to stamp events.
+    pub fn bout_time(&self) -> Duration {
+        if self.is_fencing() {
+            self.fenced + self.elapsed()
+        } else {
+            self.fenced
+        }
+    }
+
+    /// Fencing time left before passivity, only counts down while fencing.
+    pub fn passivity_remaining(&self) -> Duration {
+        let running = if self.is_fencing() {
+            self.running()
+        } else {
+            Duration::ZERO
+        };
+        (PASSIVITY_LIMIT - self.passivity - running).max(Duration::ZERO)
+    }
+
+    /// Never true in sabre since it has no passivity.
+    pub fn is_passive(&self) -> bool {
+        self.format.has_passivity()
+            && matches!(self.phase, ClockPhase::Period(_))
+            && self.passivity_remaining().is_zero()
+    }
+
+    /// Restarts the passivity timer, eg. after a passivity card.
+    pub fn reset_passivity(&mut self) {
+        self.fold();
+        self.passivity = Duration::ZERO;
+    }
+
+    /// Tells the clock about a touch, returns `PeriodEnd` if the score ends the first period.
+    pub fn touch(&mut self, scores: (u8, u8)) -> Option<BoutEventKind> {
+        self.reset_passivity();
+        let reached = |score: u8| scores.0 >= score || scores.1 >= score;
+
+        if self.phase == ClockPhase::Priority || reached(self.format.target()) {
+            self.stop();
+            self.finish_phase(ClockPhase::Finished);
+            return None;
+        }
+
+        match (self.phase, self.format.break_at_score()) {
+            (ClockPhase::Period(1), Some(break_at))
+                if reached(break_at) && !self.score_break_taken =>
+            {
+                self.stop();
+                self.score_break_taken = true;
+                if self.format.periods() > 1 {
+                    self.end_period(1);
+                    return Some(BoutEventKind::PeriodEnd);
+                }
+                // The break doesn't start a new period, so there is no event for it.
+                if !self.format.break_length().is_zero() {
+                    self.interrupted = Some(self.elapsed);
+                    self.end_period(1);
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Moves on to the next phase if time ran out.
+    pub fn tick(&mut self, scores: (u8, u8)) -> Option<BoutEventKind> {
+        let mut event = None;
+        while self.phase != ClockPhase::Finished && self.remaining().is_zero() {
+            // Any time past the end of the phase belongs to the next one.
+            let overrun = self.elapsed + self.running() - self.phase_length();
+            let was_running = self.is_running();
+            self.since = None;
+            self.elapsed = self.phase_length();
+
+            match self.phase {
+                ClockPhase::Period(period) if period < self.format.periods() => {
+                    self.end_period(period);
+                    event = Some(BoutEventKind::PeriodEnd);
+                }
+                ClockPhase::Period(_) if scores.0 == scores.1 => {
+                    self.finish_phase(ClockPhase::Priority);
+                    event = Some(BoutEventKind::TimeExpired);
+                }
+                ClockPhase::Period(_) | ClockPhase::Priority => {
+                    self.finish_phase(ClockPhase::Finished);
+                    event = Some(BoutEventKind::TimeExpired);
+                }
+                ClockPhase::Break(period) => self.resume(period),
+                ClockPhase::Finished => {}
+            }
+
+            if was_running && matches!(self.phase, ClockPhase::Break(_)) {
+                self.since = Some(self.source.now() - overrun);
+            }
+        }
+        event
+    }
+
+    fn end_period(&mut self, period: u8) {
+        if self.format.break_length().is_zero() {
+            self.resume(period);
+        } else {
+            self.finish_phase(ClockPhase::Break(period));
+            // The break runs without waiting for the referee.
+            self.since = Some(self.source.now());
+        }
+    }
+
+    /// Goes on to the next period, or back to the one the break at a score interrupted.
+    fn resume(&mut self, period: u8) {
+        match self.interrupted.take() {
+            Some(elapsed) => {
+                self.finish_phase(ClockPhase::Period(period));
+                self.fenced -= elapsed;
+                self.elapsed = elapsed;
+            }
+            None => self.finish_phase(ClockPhase::Period(period + 1)),
+        }
+    }
+
+    fn finish_phase(&mut self, next: ClockPhase) {
+        self.fold();
+        if self.is_fencing() {
+            self.fenced += self.elapsed.min(self.phase_length());
+        }
+        self.phase = next;
+        self.elapsed = Duration::ZERO;
+        self.since = None;
+    }
+
+    fn is_fencing(&self) -> bool {
+        matches!(self.phase, ClockPhase::Period(_) | ClockPhase::Priority)
+    }
+
+    fn running(&self) -> Duration {
+        self.since
+            .map(|since| self.source.now() - since)
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// Moves the running time into `elapsed` and the passivity timer, keeping the clock running.
+    fn fold(&mut self) {
+        let running = self.running();
+        self.elapsed += running;
+        if matches!(self.phase, ClockPhase::Period(_)) {
+            self.passivity += running;
+        }
+        if self.since.is_some() {
+            self.since = Some(self.source.now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use time::Duration;
+
+    use super::{BoutClock, ClockPhase, ManualTimeSource};
+    use crate::{
+        bout::{BoutEventKind, BoutFormat},
+        weapon::Weapon,
+    };
+
+    #[test]
+    fn periods_and_breaks() {
+        let time = Rc::new(ManualTimeSource::new());
+        let mut clock = BoutClock::new(BoutFormat::direct_elimination(), time.clone());
+
+        clock.start();
+        time.advance(Duration::seconds(100));
+        clock.stop();
+        time.advance(Duration::seconds(30));
+        assert_eq!(clock.remaining(), Duration::seconds(80));
+        assert_eq!(clock.tick((3, 2)), None);
+
+        clock.start();
+        time.advance(Duration::seconds(90));
+        assert_eq!(clock.tick((3, 2)), Some(BoutEventKind::PeriodEnd));
+        // The break started when the period ended, 10 seconds ago.
+        assert_eq!(clock.phase(), ClockPhase::Break(1));
+        assert_eq!(clock.remaining(), Duration::seconds(50));
+        assert_eq!(clock.bout_time(), Duration::minutes(3));
+
+        time.advance(Duration::seconds(50));
+        assert_eq!(clock.tick((3, 2)), None);
+        assert_eq!(clock.phase(), ClockPhase::Period(2));
+        assert!(!clock.is_running());
+        assert_eq!(clock.remaining(), Duration::minutes(3));
+    }
+
+    #[test]
+    fn break_at_eight() {
+        let time = Rc::new(ManualTimeSource::new());
+        let mut clock = BoutClock::new(BoutFormat::direct_elimination(), time.clone());
+
+        clock.start();
+        time.advance(Duration::seconds(40));
+        assert_eq!(clock.touch((7, 3)), None);
+        assert_eq!(clock.touch((8, 3)), Some(BoutEventKind::PeriodEnd));
+        assert_eq!(clock.phase(), ClockPhase::Break(1));
+        assert_eq!(clock.bout_time(), Duration::seconds(40));
+
+        // Only the first period ends early.
+        time.advance(Duration::minutes(1));
+        clock.tick((8, 3));
+        clock.start();
+        assert_eq!(clock.touch((9, 3)), None);
+        assert_eq!(clock.phase(), ClockPhase::Period(2));
+
+        assert_eq!(clock.touch((15, 3)), None);
+        assert_eq!(clock.phase(), ClockPhase::Finished);
+    }
+
+    #[test]
+    fn priority_minute() {
+        let time = Rc::new(ManualTimeSource::new());
+        let mut clock = BoutClock::new(BoutFormat::pool(), time.clone());
+
+        clock.start();
+        time.advance(Duration::minutes(3));
+        assert_eq!(clock.tick((2, 2)), Some(BoutEventKind::TimeExpired));
+        assert_eq!(clock.phase(), ClockPhase::Priority);
+
+        clock.start();
+        time.advance(Duration::minutes(2));
+        assert_eq!(clock.tick((2, 2)), Some(BoutEventKind::TimeExpired));
+        assert_eq!(clock.phase(), ClockPhase::Finished);
+        assert_eq!(clock.bout_time(), Duration::minutes(4));
+
+        let mut clock = BoutClock::new(BoutFormat::pool(), time.clone());
+        clock.start();
+        time.advance(Duration::minutes(4));
+        assert_eq!(clock.tick((3, 2)), Some(BoutEventKind::TimeExpired));
+        assert_eq!(clock.phase(), ClockPhase::Finished);
+    }
+
+    #[test]
+    fn touch_in_priority_ends_bout() {
+        let time = Rc::new(ManualTimeSource::new());
+        let mut clock = BoutClock::new(BoutFormat::pool(), time.clone());
+
+        clock.start();
+        time.advance(Duration::minutes(3));
+        assert_eq!(clock.tick((2, 2)), Some(BoutEventKind::TimeExpired));
+
+        clock.start();
+        time.advance(Duration::seconds(20));
+        assert_eq!(clock.touch((3, 2)), None);
+        assert_eq!(clock.phase(), ClockPhase::Finished);
+        assert!(!clock.is_running());
+        assert_eq!(clock.bout_time(), Duration::seconds(200));
+    }
+
+    #[test]
+    fn passivity_timer() {
+        let time = Rc::new(ManualTimeSource::new());
+        let mut clock = BoutClock::new(BoutFormat::direct_elimination(), time.clone());
+
+        clock.start();
+        time.advance(Duration::seconds(45));
+        clock.touch((1, 0));
+        time.advance(Duration::seconds(45));
+        assert!(!clock.is_passive());
+
+        // Time with the clock stopped does not count.
+        clock.stop();
+        time.advance(Duration::seconds(30));
+        clock.start();
+        time.advance(Duration::seconds(15));
+        assert!(clock.is_passive());
+
+        clock.reset_passivity();
+        assert_eq!(clock.passivity_remaining(), Duration::minutes(1));
+    }
+
+    #[test]
+    fn sabre() {
+        let time = Rc::new(ManualTimeSource::new());
+        let mut clock = BoutClock::new(
+            BoutFormat::direct_elimination_for(Weapon::Sabre),
+            time.clone(),
+        );
+
+        clock.start();
+        time.advance(Duration::minutes(2));
+        assert!(!clock.is_passive());
+        assert_eq!(clock.touch((8, 6)), None);
+        assert_eq!(clock.phase(), ClockPhase::Break(1));
+
+        // Sabre only has one period, the break at 8 carries on with it.
+        time.advance(Duration::minutes(1));
+        clock.tick((8, 6));
+        assert_eq!(clock.phase(), ClockPhase::Period(1));
+        assert_eq!(clock.remaining(), Duration::minutes(1));
+        assert_eq!(clock.bout_time(), Duration::minutes(2));
+
+        clock.start();
+        assert_eq!(clock.touch((9, 6)), None);
+        assert_eq!(clock.phase(), ClockPhase::Period(1));
+        time.advance(Duration::minutes(1));
+        assert_eq!(clock.tick((9, 6)), Some(BoutEventKind::TimeExpired));
+        assert_eq!(clock.phase(), ClockPhase::Finished);
+    }
+}
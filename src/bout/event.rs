@@ -0,0 +1,242 @@
+use serde::{Deserialize, Serialize};
+use time::Duration;
+
+use crate::{
+    cards::{Card, Cards},
+    pools::PoolSheetError,
+};
+
+use super::{BoutFormat, TuplePos};
+
+/// Something that happened during a bout, fencers are referred to by their position in the bout.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BoutEventKind {
+    Touch(TuplePos),
+    DoubleTouch,
+    /// Takes back the last touch given to a fencer, eg. after a video review.
+    AnnulTouch(TuplePos),
+    /// A card that was given, red cards give a touch to the opponent.
+    Card(TuplePos, Card),
+    PeriodEnd,
+    /// Priority drawn after a tie, with the seed used if the draw was seeded so it can be checked.
+    PriorityDrawn {
+        fencer: TuplePos,
+        seed: Option<u64>,
+    },
+    /// Priority given by hand instead of drawn, `TuplePos::None` takes it away.
+    PrioritySet(TuplePos),
+    /// Time ran out, the bout ends unless the scores are tied at the end of regulation time.
+    TimeExpired,
+    /// Final scores entered from the scoresheet instead of touch by touch.
+    ScoresEntered {
+        scores: (u8, u8),
+        cards: (Cards, Cards),
+        ended_on_time: bool,
+    },
+    /// One fencer's score and cards corrected by hand.
+    ScoreCorrected {
+        fencer: TuplePos,
+        score: u8,
+        cards: Cards,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BoutEvent {
+    kind: BoutEventKind,
+    /// Time on the bout clock when the event happened, if it was kept.
+    at: Option<Duration>,
+}
+
+impl BoutEvent {
+    pub fn new(kind: BoutEventKind, at: Option<Duration>) -> Self {
+        BoutEvent { kind, at }
+    }
+
+    pub fn kind(&self) -> &BoutEventKind {
+        &self.kind
+    }
+
+    pub fn at(&self) -> Option<Duration> {
+        self.at
+    }
+}
+
+/// The state of a bout after replaying its events.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct ReplayState {
+    pub(crate) scores: (u8, u8),
+    pub(crate) cards: (Cards, Cards),
+    pub(crate) priority: Option<TuplePos>,
+    pub(crate) period: u8,
+    /// `Some` once the bout is over, holding whether it ended on time.
+    pub(crate) ended: Option<bool>,
+    /// Whether any event has given a score yet, cards without a touch leave the scores unset.
+    pub(crate) scored: bool,
+    /// The bout was stopped by the clock rather than by a touch.
+    time_ran_out: bool,
+    /// Regulation time ran out with the scores tied.
+    priority_minute: bool,
+}
+
+impl ReplayState {
+    fn score_mut(&mut self, pos: TuplePos) -> Result<&mut u8, PoolSheetError> {
+        match pos {
+            TuplePos::First => Ok(&mut self.scores.0),
+            TuplePos::Second => Ok(&mut self.scores.1),
+            TuplePos::None => Err(PoolSheetError::InvalidBout),
+        }
+    }
+
+    fn touch(&mut self, pos: TuplePos, format: &BoutFormat) -> Result<(), PoolSheetError> {
+        *self.score_mut(pos)? += 1;
+        self.scored = true;
+        if self.scores.0 == format.target() || self.scores.1 == format.target() {
+            self.ended = Some(false);
+        } else if self.priority_minute {
+            // The first touch in the priority minute wins.
+            self.ended = Some(true);
+        }
+        Ok(())
+    }
+
+    fn check_running(&self) -> Result<(), PoolSheetError> {
+        match self.ended {
+            Some(_) => Err(PoolSheetError::BoutFinished),
+            None => Ok(()),
+        }
+    }
+}
+
+fn opponent(pos: TuplePos) -> TuplePos {
+    match pos {
+        TuplePos::First => TuplePos::Second,
+        TuplePos::Second => TuplePos::First,
+        TuplePos::None => TuplePos::None,
+    }
+}
+
+/// Replays events from the start, erroring on the first impossible one.
+pub(crate) fn replay(
+    events: &[BoutEvent],
+    format: &BoutFormat,
+) -> Result<ReplayState, PoolSheetError> {
+    let mut state = ReplayState {
+        period: 1,
+        ..ReplayState::default()
+    };
+
+    for event in events {
+        match event.kind {
+            BoutEventKind::Touch(pos) => {
+                state.check_running()?;
+                state.touch(pos, format)?;
+            }
+            BoutEventKind::DoubleTouch => {
+                state.check_running()?;
+                if !format.allows_double_touch() {
+                    return Err(PoolSheetError::NotAllowedForWeapon);
+                }
+                // Doubles do not count in the priority minute or when both fencers are one touch away
+                // from winning.
+                let last_touch = format.target().saturating_sub(1);
+                state.scored = true;
+                if !state.priority_minute && state.scores != (last_touch, last_touch) {
+                    state.scores.0 += 1;
+                    state.scores.1 += 1;
+                    if state.scores.0 == format.target() || state.scores.1 == format.target() {
+                        state.ended = Some(false);
+                    }
+                }
+            }
+            BoutEventKind::AnnulTouch(pos) => {
+                let score = state.score_mut(pos)?;
+                *score = score.checked_sub(1).ok_or(PoolSheetError::InvalidScore)?;
+                // Taking back the winning touch means the bout is not over,
+                // a bout stopped by the clock stays over with the corrected score.
+                if !state.time_ran_out {
+                    state.ended = None;
+                }
+            }
+            BoutEventKind::Card(pos, card) => {
+                if card.is_passivity() && !format.has_passivity() {
+                    return Err(PoolSheetError::NotAllowedForWeapon);
+                }
+                match pos {
+                    TuplePos::First => state.cards.0.add_card(card),
+                    TuplePos::Second => state.cards.1.add_card(card),
+                    TuplePos::None => return Err(PoolSheetError::InvalidBout),
+                }
+                if card.awards_touch() && state.ended.is_none() {
+                    state.touch(opponent(pos), format)?;
+                }
+            }
+            BoutEventKind::PeriodEnd => {
+                state.check_running()?;
+                state.period += 1;
+            }
+            BoutEventKind::PriorityDrawn { fencer, .. } => {
+                if fencer == TuplePos::None {
+                    return Err(PoolSheetError::InvalidBout);
+                }
+                if state.scores.0 != state.scores.1 {
+                    return Err(PoolSheetError::NotTied);
+                }
+                state.priority = Some(fencer);
+            }
+            BoutEventKind::PrioritySet(fencer) => {
+                if fencer != TuplePos::None && state.scores.0 != state.scores.1 {
+                    return Err(PoolSheetError::NotTied);
+                }
+                state.priority = Some(fencer).filter(|fencer| *fencer != TuplePos::None);
+            }
+            BoutEventKind::TimeExpired => {
+                state.check_running()?;
+                if state.scores.0 != state.scores.1
+                    || (state.priority_minute && state.priority.is_some())
+                {
+                    state.ended = Some(true);
+                    state.time_ran_out = true;
+                } else {
+                    state.priority_minute = true;
+                }
+            }
+            BoutEventKind::ScoresEntered {
+                scores,
+                cards,
+                ended_on_time,
+            } => {
+                format.validate_scores(scores.0, scores.1, ended_on_time)?;
+                if scores.0 == scores.1 && state.priority.is_none() {
+                    return Err(PoolSheetError::NoPriority);
+                }
+                state.scores = scores;
+                state.cards = cards;
+                state.ended = Some(ended_on_time);
+                state.scored = true;
+                state.time_ran_out = ended_on_time;
+            }
+            BoutEventKind::ScoreCorrected {
+                fencer,
+                score,
+                cards,
+            } => {
+                format.validate_score(score)?;
+                *state.score_mut(fencer)? = score;
+                match fencer {
+                    TuplePos::First => state.cards.0 = cards,
+                    TuplePos::Second => state.cards.1 = cards,
+                    TuplePos::None => unreachable!("score_mut checked the position"),
+                }
+                state.scored = true;
+                if state.scores.0 == format.target() || state.scores.1 == format.target() {
+                    state.ended = Some(false);
+                } else if !state.time_ran_out {
+                    state.ended = None;
+                }
+            }
+        }
+    }
+
+    Ok(state)
+}
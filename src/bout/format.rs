@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+use time::Duration;
+
+use crate::{pools::PoolSheetError, weapon::Weapon};
+
+/// How a bout is fenced.
+///
+/// # Usage
+/// ```
+/// use fencing_sport_lib::bout::BoutFormat;
+///
+/// let pool = BoutFormat::pool();
+/// assert_eq!(pool.target(), 5);
+///
+/// let de = BoutFormat::direct_elimination();
+/// assert_eq!(de.periods(), 3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoutFormat {
+    target: u8,
+    periods: u8,
+    period_length: Duration,
+    break_length: Duration,
+    priority_length: Duration,
+    /// Score that ends the first period early for a break, eg. 8 in a 15 touch bout.
+    #[serde(default)]
+    break_at_score: Option<u8>,
+    /// Weapon specific rules are only checked when the weapon is known.
+    #[serde(default)]
+    weapon: Option<Weapon>,
+}
+
+impl BoutFormat {
+    pub fn new(
+        target: u8,
+        periods: u8,
+        period_length: Duration,
+        break_length: Duration,
+        priority_length: Duration,
+    ) -> BoutFormat {
+        BoutFormat {
+            target,
+            periods,
+            period_length,
+            break_length,
+            priority_length,
+            break_at_score: None,
+            weapon: None,
+        }
+    }
+
+    /// 5 touches in a single period of 3 minutes.
+    pub fn pool() -> BoutFormat {
+        BoutFormat::new(
+            5,
+            1,
+            Duration::minutes(3),
+            Duration::ZERO,
+            Duration::minutes(1),
+        )
+    }
+
+    /// 15 touches in 3 periods of 3 minutes, with a break at 8.
+    pub fn direct_elimination() -> BoutFormat {
+        let mut format = BoutFormat::new(
+            15,
+            3,
+            Duration::minutes(3),
+            Duration::minutes(1),
+            Duration::minutes(1),
+        );
+        format.set_break_at_score(Some(8));
+        format
+    }
+
+    /// 9 legs of 3 minutes fenced to 45.
+    pub fn team_relay() -> BoutFormat {
+        BoutFormat::new(
+            45,
+            9,
+            Duration::minutes(3),
+            Duration::ZERO,
+            Duration::minutes(1),
+        )
+    }
+
+    /// A pool bout for `weapon`.
+    pub fn pool_for(weapon: Weapon) -> BoutFormat {
+        let mut format = BoutFormat::pool();
+        format.weapon = Some(weapon);
+        format
+    }
+
+    /// Sabre has one period with a break at 8, foil and epee don't break at 8.
+    pub fn direct_elimination_for(weapon: Weapon) -> BoutFormat {
+        let mut format = BoutFormat::direct_elimination();
+        format.weapon = Some(weapon);
+        match weapon {
+            Weapon::Sabre => format.periods = 1,
+            Weapon::Foil | Weapon::Epee => format.break_at_score = None,
+        }
+        format
+    }
+
+    pub fn target(&self) -> u8 {
+        self.target
+    }
+
+    pub fn periods(&self) -> u8 {
+        self.periods
+    }
+
+    pub fn period_length(&self) -> Duration {
+        self.period_length
+    }
+
+    pub fn break_length(&self) -> Duration {
+        self.break_length
+    }
+
+    pub fn priority_length(&self) -> Duration {
+        self.priority_length
+    }
+
+    pub fn break_at_score(&self) -> Option<u8> {
+        self.break_at_score
+    }
+
+    pub fn set_break_at_score(&mut self, score: Option<u8>) {
+        self.break_at_score = score;
+    }
+
+    pub fn weapon(&self) -> Option<Weapon> {
+        self.weapon
+    }
+
+    pub fn set_weapon(&mut self, weapon: Option<Weapon>) {
+        self.weapon = weapon;
+    }
+
+    /// Double touches are allowed unless the weapon is known to not have them.
+    pub fn allows_double_touch(&self) -> bool {
+        self.weapon
+            .is_none_or(|weapon| weapon.allows_double_touch())
+    }
+
+    pub fn has_passivity(&self) -> bool {
+        self.weapon.is_none_or(|weapon| weapon.has_passivity())
+    }
+
+    /// Checks a single score is not over the target.
+    pub fn validate_score(&self, score: u8) -> Result<(), PoolSheetError> {
+        if score > self.target {
+            Err(PoolSheetError::InvalidScore)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks the final scores of a bout.
+    pub fn validate_scores(
+        &self,
+        score_a: u8,
+        score_b: u8,
+        ended_on_time: bool,
+    ) -> Result<(), PoolSheetError> {
+        self.validate_score(score_a)?;
+        self.validate_score(score_b)?;
+
+        let at_target = [score_a, score_b]
+            .iter()
+            .filter(|score| **score == self.target)
+            .count();
+        match (ended_on_time, at_target) {
+            (false, 1) | (true, 0) => Ok(()),
+            _ => Err(PoolSheetError::InvalidScore),
+        }
+    }
+}
+
+impl Default for BoutFormat {
+    fn default() -> Self {
+        BoutFormat::pool()
+    }
+}
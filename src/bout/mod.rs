@@ -1,15 +1,30 @@
 use std::{borrow::Borrow, cmp::Ordering};
 
-use crate::{cards::Cards, fencer::Fencer, pools::PoolSheetError};
-
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use time::Duration;
+
+use crate::{
+    cards::{Cards, Offence, PenaltyOutcome},
+    fencer::Fencer,
+    pools::PoolSheetError,
+    weapon::Weapon,
+};
+
+mod clock;
+pub use clock::{BoutClock, ClockPhase, ManualTimeSource, SystemTimeSource, TimeSource};
+mod event;
+pub use event::{BoutEvent, BoutEventKind};
+mod format;
+pub use format::BoutFormat;
 mod score;
 pub use score::FencerScore;
 mod winner;
-pub use winner::BoutWinner;
+pub use winner::{BoutWinner, DecisionReason};
 
 mod versus;
-pub(crate) use versus::TuplePos;
-pub use versus::{FencerVs, VersusError};
+pub use versus::{FencerVs, TuplePos, VersusError};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Bout<U: Fencer, T: Borrow<U> + Clone> {
@@ -17,13 +32,38 @@ pub struct Bout<U: Fencer, T: Borrow<U> + Clone> {
     pub(crate) scores: (Option<u8>, Option<u8>),
     pub(crate) cards: (Cards, Cards),
     pub(crate) priority: TuplePos,
+    pub(crate) format: BoutFormat,
+    pub(crate) ended_on_time: bool,
+    pub(crate) events: Vec<BoutEvent>,
+    /// Set when the event log has been started but the bout is not over yet.
+    pub(crate) in_progress: bool,
+    pub(crate) winner: BoutWinner<U, T>,
 }
 
 impl<U: Fencer, T: Borrow<U> + Clone> Bout<U, T> {
+    /// Sets the final scores of a bout that ended with a fencer reaching the target.
     pub fn update_scores<S: Borrow<U>>(
         &mut self,
         score_a: FencerScore<U, S>,
         score_b: FencerScore<U, S>,
+    ) -> Result<(), PoolSheetError> {
+        self.set_final_scores(score_a, score_b, false)
+    }
+
+    /// Sets the final scores of a bout that ended when time ran out.
+    pub fn update_scores_on_time<S: Borrow<U>>(
+        &mut self,
+        score_a: FencerScore<U, S>,
+        score_b: FencerScore<U, S>,
+    ) -> Result<(), PoolSheetError> {
+        self.set_final_scores(score_a, score_b, true)
+    }
+
+    fn set_final_scores<S: Borrow<U>>(
+        &mut self,
+        score_a: FencerScore<U, S>,
+        score_b: FencerScore<U, S>,
+        ended_on_time: bool,
     ) -> Result<(), PoolSheetError> {
         let pos_a = self.fencers.pos(score_a.fencer.borrow());
         let pos_b = self.fencers.pos(score_b.fencer.borrow());
@@ -56,33 +96,51 @@ impl<U: Fencer, T: Borrow<U> + Clone> Bout<U, T> {
             return Err(PoolSheetError::NoBoutFound);
         }
 
+        self.format
+            .validate_scores(score_0, score_1, ended_on_time)?;
+        if score_0 == score_1 && self.priority == TuplePos::None {
+            return Err(PoolSheetError::NoPriority);
+        }
+
+        if !self.events.is_empty() {
+            return self.record(BoutEventKind::ScoresEntered {
+                scores: (score_0, score_1),
+                cards: (cards_0, cards_1),
+                ended_on_time,
+            });
+        }
+
         self.scores = (Some(score_0), Some(score_1));
         self.cards = (cards_0, cards_1);
+        self.ended_on_time = ended_on_time;
         Ok(())
     }
 
+    /// The final scores, `None` until the bout is over.
     pub fn get_scores(&self) -> Option<(u8, u8)> {
+        if self.in_progress {
+            return None;
+        }
         self.scores.0.zip(self.scores.1)
     }
 
+    /// Corrects a single score and the fencer's cards, the correction is kept in the event log.
     pub fn set_score<V: Borrow<U>>(
         &mut self,
         fencer_score: FencerScore<U, V>,
     ) -> Result<(), PoolSheetError> {
-        match self.fencers.pos(fencer_score.fencer.borrow()) {
-            TuplePos::First => {
-                self.scores.0 = Some(fencer_score.score);
-                self.cards.0 = fencer_score.cards;
-            }
-            TuplePos::Second => {
-                self.scores.1 = Some(fencer_score.score);
-                self.cards.1 = fencer_score.cards;
-            }
-            TuplePos::None => return Err(PoolSheetError::NoBoutFound),
+        let fencer = self.fencers.pos(fencer_score.fencer.borrow());
+        if fencer == TuplePos::None {
+            return Err(PoolSheetError::NoBoutFound);
         }
-        Ok(())
+        self.record(BoutEventKind::ScoreCorrected {
+            fencer,
+            score: fencer_score.score,
+            cards: fencer_score.cards,
+        })
     }
 
+    /// The score of a fencer, while the bout is being fenced this is the current score.
     pub fn get_score<V: Borrow<U>>(&self, fencer: V) -> Option<u8> {
         match self.fencers.pos(fencer.borrow()) {
             TuplePos::First => self.scores.0,
@@ -100,8 +158,124 @@ impl<U: Fencer, T: Borrow<U> + Clone> Bout<U, T> {
         Ok(())
     }
 
+    /// Clears the scores and the event log.
     pub fn unset_scores(&mut self) {
         self.scores = (None, None);
+        self.events.clear();
+        self.in_progress = false;
+    }
+
+    pub fn events(&self) -> &[BoutEvent] {
+        &self.events
+    }
+
+    /// Errors without changing the bout if the event couldn't have happened.
+    pub fn record(&mut self, kind: BoutEventKind) -> Result<(), PoolSheetError> {
+        self.record_at(kind, None)
+    }
+
+    /// Adds an event that happened at `at` on the bout clock.
+    pub fn record_at(
+        &mut self,
+        kind: BoutEventKind,
+        at: Option<Duration>,
+    ) -> Result<(), PoolSheetError> {
+        let mut events = self.events.clone();
+        // Scores entered before the log was started are kept as its first event.
+        if events.is_empty() {
+            if let Some(scores) = self.get_scores() {
+                events.push(BoutEvent::new(
+                    BoutEventKind::ScoresEntered {
+                        scores,
+                        cards: self.cards,
+                        ended_on_time: self.ended_on_time,
+                    },
+                    None,
+                ));
+            }
+        }
+        events.push(BoutEvent::new(kind, at));
+
+        self.apply_events(events)
+    }
+
+    pub fn record_touch<V: Borrow<U>>(&mut self, fencer: V) -> Result<(), PoolSheetError> {
+        let pos = self.fencers.pos(fencer.borrow());
+        self.record(BoutEventKind::Touch(pos))
+    }
+
+    /// Takes back the last touch given to `fencer`.
+    pub fn annul_touch<V: Borrow<U>>(&mut self, fencer: V) -> Result<(), PoolSheetError> {
+        let pos = self.fencers.pos(fencer.borrow());
+        self.record(BoutEventKind::AnnulTouch(pos))
+    }
+
+    /// Removes the last event and replays the rest of the log.
+    pub fn undo(&mut self) -> Option<BoutEvent> {
+        let mut events = self.events.clone();
+        let event = events.pop()?;
+        if events.is_empty() {
+            self.unset_scores();
+            self.cards = (Cards::default(), Cards::default());
+            self.priority = TuplePos::None;
+            self.ended_on_time = false;
+        } else {
+            self.apply_events(events)
+                .expect("The start of a valid log is valid");
+        }
+        Some(event)
+    }
+
+    /// Replays `events` and takes the scores, cards and priority from them.
+    pub(crate) fn apply_events(&mut self, events: Vec<BoutEvent>) -> Result<(), PoolSheetError> {
+        let state = event::replay(&events, &self.format)?;
+        self.scores = if state.scored {
+            (Some(state.scores.0), Some(state.scores.1))
+        } else {
+            (None, None)
+        };
+        self.cards = state.cards;
+        self.priority = state.priority.unwrap_or(TuplePos::None);
+        self.ended_on_time = state.ended == Some(true);
+        self.in_progress = state.ended.is_none();
+        self.events = events;
+        Ok(())
+    }
+
+    pub fn get_cards<V: Borrow<U>>(&self, fencer: V) -> Option<&Cards> {
+        match self.fencers.pos(fencer.borrow()) {
+            TuplePos::First => Some(&self.cards.0),
+            TuplePos::Second => Some(&self.cards.1),
+            TuplePos::None => None,
+        }
+    }
+
+    /// Issues a card to `fencer` for an offence, red cards add a touch to the opponent's score.
+    pub fn issue_card<V: Borrow<U>>(
+        &mut self,
+        fencer: V,
+        offence: Offence,
+    ) -> Result<PenaltyOutcome, PoolSheetError> {
+        self.issue_card_with_carry_over(fencer, offence, &Cards::default())
+    }
+
+    /// Same as `issue_card`, with passivity cards the fencer received in earlier bouts.
+    pub fn issue_card_with_carry_over<V: Borrow<U>>(
+        &mut self,
+        fencer: V,
+        offence: Offence,
+        carried: &Cards,
+    ) -> Result<PenaltyOutcome, PoolSheetError> {
+        let pos = self.fencers.pos(fencer.borrow());
+        let mut cards = match pos {
+            TuplePos::First => self.cards.0,
+            TuplePos::Second => self.cards.1,
+            TuplePos::None => return Err(PoolSheetError::NoBoutFound),
+        };
+
+        let outcome = cards.issue(offence, carried);
+        self.record(BoutEventKind::Card(pos, *outcome.card()))?;
+        Ok(outcome)
     }
 
     pub fn get_fencers(&self) -> (&U, &U) {
@@ -112,22 +286,41 @@ impl<U: Fencer, T: Borrow<U> + Clone> Bout<U, T> {
         self.fencers.get_fencers_owned()
     }
 
+    /// Errors if the scores aren't tied.
+    pub fn draw_priority<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<&U, PoolSheetError> {
+        self.record_priority_draw(rng.gen_bool(0.5), None)
+    }
+
+    /// Same as `draw_priority`, the seed is kept in the event log.
+    pub fn draw_priority_seeded(&mut self, seed: u64) -> Result<&U, PoolSheetError> {
+        let first = ChaCha8Rng::seed_from_u64(seed).gen_bool(0.5);
+        self.record_priority_draw(first, Some(seed))
+    }
+
+    fn record_priority_draw(
+        &mut self,
+        first: bool,
+        seed: Option<u64>,
+    ) -> Result<&U, PoolSheetError> {
+        let fencer = if first {
+            TuplePos::First
+        } else {
+            TuplePos::Second
+        };
+        self.record(BoutEventKind::PriorityDrawn { fencer, seed })?;
+        Ok(self.get_priority().expect("Priority was just recorded"))
+    }
+
+    /// Gives priority by hand, eg. after a coin toss, `None` takes it away.
     pub fn set_priority<V: Borrow<U>>(&mut self, fencer: Option<V>) -> Result<(), PoolSheetError> {
-        match fencer {
-            Some(fencer) => {
-                let pos = self.fencers.pos(fencer.borrow());
-                if pos == TuplePos::None {
-                    Err(PoolSheetError::NoBoutFound)
-                } else {
-                    self.priority = pos;
-                    Ok(())
-                }
-            }
-            None => {
-                self.priority = TuplePos::None;
-                Ok(())
-            }
-        }
+        let pos = match fencer {
+            Some(fencer) => match self.fencers.pos(fencer.borrow()) {
+                TuplePos::None => return Err(PoolSheetError::NoBoutFound),
+                pos => pos,
+            },
+            None => TuplePos::None,
+        };
+        self.record(BoutEventKind::PrioritySet(pos))
     }
 
     pub fn get_priority(&self) -> Option<&U> {
@@ -138,8 +331,12 @@ impl<U: Fencer, T: Borrow<U> + Clone> Bout<U, T> {
         }
     }
 
+    /// The winner, a winner set by the bout committee takes precedence over the scores.
     pub fn get_winner(&self) -> Option<&U> {
-        let (score_a, score_b) = self.scores.0.zip(self.scores.1)?;
+        if let BoutWinner::Manual(fencer, _) = &self.winner {
+            return Some(fencer.borrow());
+        }
+        let (score_a, score_b) = self.get_scores()?;
         match score_a.cmp(&score_b) {
             Ordering::Greater => Some(self.fencers.0.borrow()),
             Ordering::Less => Some(self.fencers.1.borrow()),
@@ -147,12 +344,109 @@ impl<U: Fencer, T: Borrow<U> + Clone> Bout<U, T> {
         }
     }
 
+    pub fn get_bout_winner(&self) -> &BoutWinner<U, T> {
+        &self.winner
+    }
+
+    /// Winner decided by the bout committee, the scores are kept as they were.
+    pub fn set_winner<V: Borrow<U>>(
+        &mut self,
+        fencer: V,
+        reason: DecisionReason,
+    ) -> Result<(), PoolSheetError> {
+        let winner = match self.fencers.pos(fencer.borrow()) {
+            TuplePos::First => self.fencers.0.clone(),
+            TuplePos::Second => self.fencers.1.clone(),
+            TuplePos::None => return Err(PoolSheetError::NoBoutFound),
+        };
+        self.winner = BoutWinner::Manual(winner, reason);
+        Ok(())
+    }
+
+    /// `fencer` abandoned the bout, their opponent wins it.
+    pub fn abandon<V: Borrow<U>>(&mut self, fencer: V) -> Result<(), PoolSheetError> {
+        let opponent = match self.fencers.pos(fencer.borrow()) {
+            TuplePos::First => self.fencers.1.clone(),
+            TuplePos::Second => self.fencers.0.clone(),
+            TuplePos::None => return Err(PoolSheetError::NoBoutFound),
+        };
+        self.winner = BoutWinner::Manual(opponent, DecisionReason::Abandonment);
+        Ok(())
+    }
+
+    /// Goes back to working out the winner from the scores.
+    pub fn clear_winner(&mut self) {
+        self.winner = BoutWinner::default();
+    }
+
+    /// Position of the manual winner and why they won, used when saving a bout.
+    pub(crate) fn manual_winner(&self) -> Option<(TuplePos, DecisionReason)> {
+        match &self.winner {
+            BoutWinner::Auto(_) => None,
+            BoutWinner::Manual(fencer, reason) => {
+                Some((self.fencers.pos(Borrow::<U>::borrow(fencer)), *reason))
+            }
+        }
+    }
+
+    /// Missing scores count as 0, the winner of an abandoned bout gets the target.
+    pub fn touches(&self) -> (u8, u8) {
+        let (mut touches_a, mut touches_b) =
+            (self.scores.0.unwrap_or(0), self.scores.1.unwrap_or(0));
+        let target = self.format.target();
+        match self.manual_winner() {
+            Some((TuplePos::First, DecisionReason::Abandonment)) => {
+                touches_a = touches_a.max(target)
+            }
+            Some((TuplePos::Second, DecisionReason::Abandonment)) => {
+                touches_b = touches_b.max(target)
+            }
+            _ => {}
+        }
+        (touches_a, touches_b)
+    }
+
+    pub fn get_format(&self) -> &BoutFormat {
+        &self.format
+    }
+
+    pub fn set_format(&mut self, format: BoutFormat) {
+        self.format = format;
+    }
+
+    pub fn get_weapon(&self) -> Option<Weapon> {
+        self.format.weapon()
+    }
+
+    /// Sets the weapon, errors without changing the bout if the event log breaks its rules.
+    pub fn set_weapon(&mut self, weapon: Option<Weapon>) -> Result<(), PoolSheetError> {
+        let mut format = self.format;
+        format.set_weapon(weapon);
+        event::replay(&self.events, &format)?;
+        self.format = format;
+        Ok(())
+    }
+
+    pub fn ended_on_time(&self) -> bool {
+        self.ended_on_time
+    }
+
+    /// Creates a bout fenced to 5 touches.
     pub fn new(fencers: FencerVs<U, T>) -> Self {
+        Bout::with_format(fencers, BoutFormat::pool())
+    }
+
+    pub fn with_format(fencers: FencerVs<U, T>, format: BoutFormat) -> Self {
         Bout {
             fencers,
             scores: (None, None),
             cards: (Cards::default(), Cards::default()),
             priority: TuplePos::None,
+            format,
+            ended_on_time: false,
+            events: Vec::new(),
+            in_progress: false,
+            winner: BoutWinner::default(),
         }
     }
 }
@@ -161,45 +455,343 @@ impl<U: Fencer, T: Borrow<U> + Clone> Bout<U, T> {
 mod tests {
     use std::rc::Rc;
 
-    use crate::fencer::SimpleFencer;
+    use crate::{
+        cards::{Card, Cards, Offence},
+        fencer::SimpleFencer,
+        weapon::Weapon,
+    };
 
-    use super::{Bout, FencerVs};
+    use super::{Bout, BoutEventKind, BoutFormat, DecisionReason, FencerScore, FencerVs, TuplePos};
 
-    const EXPECTED_BOUT: &str = r#"Bout { fencers: FencerVs(SimpleFencer { name: "Alice", clubs: [] }, SimpleFencer { name: "Bob", clubs: [] }, PhantomData<fencing_sport_lib::fencer::SimpleFencer>), scores: (None, None), cards: (Cards { yellow: 0, red: 0, group3red: 0, black: 0, passivity_yellow: 0, passivity_red: 0, passivity_black: 0 }, Cards { yellow: 0, red: 0, group3red: 0, black: 0, passivity_yellow: 0, passivity_red: 0, passivity_black: 0 }), priority: None }"#;
+    // The format is formatted separately, `time::Duration`'s Debug output changes between versions.
+    fn expected_bout() -> String {
+        format!(
+            r#"Bout {{ fencers: FencerVs(SimpleFencer {{ identity: Identity {{ name: "Alice", id: "Alice" }}, clubs: [] }}, SimpleFencer {{ identity: Identity {{ name: "Bob", id: "Bob" }}, clubs: [] }}, PhantomData<fencing_sport_lib::fencer::SimpleFencer>), scores: (None, None), cards: (Cards {{ yellow: 0, red: 0, group3red: 0, black: 0, passivity_yellow: 0, passivity_red: 0, passivity_black: 0 }}, Cards {{ yellow: 0, red: 0, group3red: 0, black: 0, passivity_yellow: 0, passivity_red: 0, passivity_black: 0 }}), priority: None, format: {:?}, ended_on_time: false, events: [], in_progress: false, winner: Auto(PhantomData<fencing_sport_lib::fencer::SimpleFencer>) }}"#,
+            BoutFormat::pool()
+        )
+    }
 
     #[test]
     fn bout_owned_test() {
-        let fencer_a = SimpleFencer::new("Alice");
-        let fencer_b = SimpleFencer::new("Bob");
+        let fencer_a = SimpleFencer::new("Alice", "Alice");
+        let fencer_b = SimpleFencer::new("Bob", "Bob");
 
         let versus: FencerVs<SimpleFencer, SimpleFencer> =
             FencerVs::new(fencer_a, fencer_b).unwrap();
         let bout = Bout::new(versus);
 
-        assert_eq!(format!("{bout:?}"), EXPECTED_BOUT);
+        assert_eq!(format!("{bout:?}"), expected_bout());
     }
 
     #[test]
     fn bout_box_test() {
-        let fencer_a = Box::new(SimpleFencer::new("Alice"));
-        let fencer_b = Box::new(SimpleFencer::new("Bob"));
+        let fencer_a = Box::new(SimpleFencer::new("Alice", "Alice"));
+        let fencer_b = Box::new(SimpleFencer::new("Bob", "Bob"));
 
         let versus: FencerVs<SimpleFencer, Box<SimpleFencer>> =
             FencerVs::new(fencer_a, fencer_b).unwrap();
         let bout = Bout::new(versus);
 
-        assert_eq!(format!("{bout:?}"), EXPECTED_BOUT);
+        assert_eq!(format!("{bout:?}"), expected_bout());
     }
 
     #[test]
     fn bout_rc_test() {
-        let fencer_a = Rc::new(SimpleFencer::new("Alice"));
-        let fencer_b = Rc::new(SimpleFencer::new("Bob"));
+        let fencer_a = Rc::new(SimpleFencer::new("Alice", "Alice"));
+        let fencer_b = Rc::new(SimpleFencer::new("Bob", "Bob"));
 
         let versus: FencerVs<SimpleFencer, Rc<SimpleFencer>> =
             FencerVs::new(fencer_a, fencer_b).unwrap();
         let bout = Bout::new(versus);
 
-        assert_eq!(format!("{bout:?}"), EXPECTED_BOUT);
+        assert_eq!(format!("{bout:?}"), expected_bout());
+    }
+
+    #[test]
+    fn red_card_gives_touch() {
+        let fencer_a = SimpleFencer::new("Alice", "Alice");
+        let fencer_b = SimpleFencer::new("Bob", "Bob");
+        let versus: FencerVs<SimpleFencer, SimpleFencer> =
+            FencerVs::new(fencer_a.clone(), fencer_b.clone()).unwrap();
+        let mut bout = Bout::new(versus);
+
+        bout.issue_card(&fencer_a, Offence::Group1).unwrap();
+        assert_eq!(bout.get_score(&fencer_b), None);
+
+        let outcome = bout.issue_card(&fencer_a, Offence::Group1).unwrap();
+        assert_eq!(*outcome.card(), Card::Red);
+        assert_eq!(bout.get_score(&fencer_b), Some(1));
+        assert_eq!(*bout.get_cards(&fencer_a).unwrap().red(), 1);
+        assert_eq!(bout.get_winner(), None);
+    }
+
+    #[test]
+    fn replay_event_log() {
+        let fencer_a = SimpleFencer::new("Alice", "Alice");
+        let fencer_b = SimpleFencer::new("Bob", "Bob");
+        let versus: FencerVs<SimpleFencer, SimpleFencer> =
+            FencerVs::new(fencer_a.clone(), fencer_b.clone()).unwrap();
+        let mut bout = Bout::new(versus);
+
+        for _ in 0..3 {
+            bout.record_touch(&fencer_a).unwrap();
+        }
+        bout.record(BoutEventKind::DoubleTouch).unwrap();
+        bout.annul_touch(&fencer_b).unwrap();
+        bout.record_touch(&fencer_b).unwrap();
+        assert_eq!(bout.get_score(&fencer_a), Some(4));
+        assert_eq!(bout.get_score(&fencer_b), Some(1));
+        assert_eq!(bout.get_winner(), None);
+
+        bout.record_touch(&fencer_a).unwrap();
+        assert_eq!(bout.get_scores(), Some((5, 1)));
+        assert_eq!(bout.get_winner(), Some(&fencer_a));
+        assert!(bout.record_touch(&fencer_b).is_err());
+        assert_eq!(bout.events().len(), 7);
+
+        let undone = bout.undo().unwrap();
+        assert_eq!(*undone.kind(), BoutEventKind::Touch(TuplePos::First));
+        assert_eq!(bout.get_scores(), None);
+        assert_eq!(bout.get_score(&fencer_a), Some(4));
+    }
+
+    #[test]
+    fn manual_changes_are_logged() {
+        let fencer_a = SimpleFencer::new("Alice", "Alice");
+        let fencer_b = SimpleFencer::new("Bob", "Bob");
+        let score = |fencer: &SimpleFencer, score| {
+            FencerScore::new(fencer.clone(), score, Cards::default())
+        };
+        let mut bout = Bout::new(FencerVs::new(fencer_a.clone(), fencer_b.clone()).unwrap());
+
+        bout.set_priority(Some(&fencer_b)).unwrap();
+        assert_eq!(
+            *bout.events()[0].kind(),
+            BoutEventKind::PrioritySet(TuplePos::Second)
+        );
+        bout.update_scores_on_time(score(&fencer_a, 2), score(&fencer_b, 2))
+            .unwrap();
+        assert_eq!(bout.get_winner(), Some(&fencer_b));
+
+        bout.set_score(score(&fencer_a, 5)).unwrap();
+        assert_eq!(bout.get_scores(), Some((5, 2)));
+        assert_eq!(bout.get_winner(), Some(&fencer_a));
+        assert_eq!(bout.events().len(), 3);
+        assert!(bout.set_priority(Some(&fencer_a)).is_err());
+
+        bout.undo();
+        assert_eq!(bout.get_winner(), Some(&fencer_b));
+    }
+
+    #[test]
+    fn annul_touch_after_time_ran_out() {
+        let fencer_a = SimpleFencer::new("Alice", "Alice");
+        let fencer_b = SimpleFencer::new("Bob", "Bob");
+        let mut bout = Bout::new(FencerVs::new(fencer_a.clone(), fencer_b.clone()).unwrap());
+
+        for fencer in [&fencer_a, &fencer_a, &fencer_a, &fencer_b] {
+            bout.record_touch(fencer).unwrap();
+        }
+        bout.record(BoutEventKind::TimeExpired).unwrap();
+        bout.annul_touch(&fencer_a).unwrap();
+        assert_eq!(bout.get_scores(), Some((2, 1)));
+        assert!(bout.ended_on_time());
+        assert!(bout.record_touch(&fencer_b).is_err());
+    }
+
+    #[test]
+    fn double_touch_at_last_touch() {
+        let fencer_a = SimpleFencer::new("Alice", "Alice");
+        let fencer_b = SimpleFencer::new("Bob", "Bob");
+        let versus: FencerVs<SimpleFencer, SimpleFencer> =
+            FencerVs::new(fencer_a.clone(), fencer_b.clone()).unwrap();
+        let mut bout = Bout::new(versus);
+
+        for _ in 0..4 {
+            bout.record(BoutEventKind::DoubleTouch).unwrap();
+        }
+        bout.record(BoutEventKind::DoubleTouch).unwrap();
+        assert_eq!(bout.get_score(&fencer_a), Some(4));
+        assert_eq!(bout.get_score(&fencer_b), Some(4));
+
+        // Tied when time runs out, so the bout goes on until priority is drawn.
+        bout.record(BoutEventKind::TimeExpired).unwrap();
+        assert_eq!(bout.get_winner(), None);
+        bout.record(BoutEventKind::PriorityDrawn {
+            fencer: TuplePos::Second,
+            seed: None,
+        })
+        .unwrap();
+        bout.record(BoutEventKind::TimeExpired).unwrap();
+        assert_eq!(bout.get_winner(), Some(&fencer_b));
+        assert!(bout.ended_on_time());
+
+        // A touch in the priority minute ends the bout.
+        let mut bout = Bout::new(FencerVs::new(fencer_a.clone(), fencer_b.clone()).unwrap());
+        for _ in 0..2 {
+            bout.record(BoutEventKind::DoubleTouch).unwrap();
+        }
+        bout.record(BoutEventKind::TimeExpired).unwrap();
+        bout.record(BoutEventKind::PriorityDrawn {
+            fencer: TuplePos::Second,
+            seed: None,
+        })
+        .unwrap();
+        bout.record(BoutEventKind::Touch(TuplePos::First)).unwrap();
+        assert_eq!(bout.get_scores(), Some((3, 2)));
+        assert_eq!(bout.get_winner(), Some(&fencer_a));
+        assert!(bout.ended_on_time());
+        assert!(bout.record(BoutEventKind::Touch(TuplePos::Second)).is_err());
+
+        // Priority set before time runs out doesn't end the bout early, and doubles in the
+        // priority minute don't score.
+        let mut bout = Bout::new(FencerVs::new(fencer_a.clone(), fencer_b.clone()).unwrap());
+        bout.record(BoutEventKind::PrioritySet(TuplePos::Second))
+            .unwrap();
+        bout.record(BoutEventKind::Touch(TuplePos::First)).unwrap();
+        bout.record(BoutEventKind::Touch(TuplePos::Second)).unwrap();
+        assert_eq!(bout.get_winner(), None);
+        bout.record(BoutEventKind::TimeExpired).unwrap();
+        bout.record(BoutEventKind::DoubleTouch).unwrap();
+        assert_eq!(bout.get_winner(), None);
+        bout.record(BoutEventKind::TimeExpired).unwrap();
+        assert_eq!(bout.get_scores(), Some((1, 1)));
+        assert_eq!(bout.get_winner(), Some(&fencer_b));
+    }
+
+    #[test]
+    fn scores_checked_against_format() {
+        let fencer_a = SimpleFencer::new("Alice", "Alice");
+        let fencer_b = SimpleFencer::new("Bob", "Bob");
+        let versus: FencerVs<SimpleFencer, SimpleFencer> =
+            FencerVs::new(fencer_a.clone(), fencer_b.clone()).unwrap();
+        let mut bout = Bout::new(versus);
+        let score = |fencer: &SimpleFencer, score| {
+            FencerScore::new(fencer.clone(), score, Cards::default())
+        };
+
+        assert!(bout
+            .update_scores(score(&fencer_a, 9), score(&fencer_b, 3))
+            .is_err());
+        assert!(bout
+            .update_scores(score(&fencer_a, 4), score(&fencer_b, 3))
+            .is_err());
+        assert!(bout
+            .update_scores(score(&fencer_a, 5), score(&fencer_b, 5))
+            .is_err());
+        assert_eq!(bout.get_scores(), None);
+
+        bout.update_scores_on_time(score(&fencer_a, 4), score(&fencer_b, 3))
+            .unwrap();
+        assert!(bout.ended_on_time());
+        bout.update_scores(score(&fencer_a, 5), score(&fencer_b, 3))
+            .unwrap();
+        assert!(!bout.ended_on_time());
+
+        let versus: FencerVs<SimpleFencer, SimpleFencer> =
+            FencerVs::new(fencer_a.clone(), fencer_b.clone()).unwrap();
+        let mut bout = Bout::with_format(versus, BoutFormat::direct_elimination());
+        bout.update_scores(score(&fencer_a, 15), score(&fencer_b, 9))
+            .unwrap();
+    }
+
+    #[test]
+    fn weapon_rules() {
+        let fencer_a = SimpleFencer::new("Alice", "Alice");
+        let fencer_b = SimpleFencer::new("Bob", "Bob");
+        let bout_in = |weapon| {
+            let versus: FencerVs<SimpleFencer, SimpleFencer> =
+                FencerVs::new(fencer_a.clone(), fencer_b.clone()).unwrap();
+            Bout::with_format(versus, BoutFormat::pool_for(weapon))
+        };
+
+        let mut foil = bout_in(Weapon::Foil);
+        assert!(foil.record(BoutEventKind::DoubleTouch).is_err());
+        assert!(foil.events().is_empty());
+        foil.issue_card(&fencer_a, Offence::Passivity).unwrap();
+
+        let mut epee = bout_in(Weapon::Epee);
+        epee.record(BoutEventKind::DoubleTouch).unwrap();
+        assert_eq!(epee.get_score(&fencer_b), Some(1));
+        assert!(epee.set_weapon(Some(Weapon::Foil)).is_err());
+        assert_eq!(epee.get_weapon(), Some(Weapon::Epee));
+
+        let mut sabre = bout_in(Weapon::Sabre);
+        assert!(sabre.issue_card(&fencer_a, Offence::Passivity).is_err());
+        assert!(sabre.record(BoutEventKind::DoubleTouch).is_err());
+
+        let sabre_de = BoutFormat::direct_elimination_for(Weapon::Sabre);
+        assert_eq!(sabre_de.periods(), 1);
+        assert_eq!(sabre_de.break_at_score(), Some(8));
+        assert_eq!(
+            BoutFormat::direct_elimination_for(Weapon::Epee).break_at_score(),
+            None
+        );
+    }
+
+    #[test]
+    fn priority_draw() {
+        let fencer_a = SimpleFencer::new("Alice", "Alice");
+        let fencer_b = SimpleFencer::new("Bob", "Bob");
+        let new_bout = || {
+            let versus: FencerVs<SimpleFencer, SimpleFencer> =
+                FencerVs::new(fencer_a.clone(), fencer_b.clone()).unwrap();
+            Bout::new(versus)
+        };
+        let score = |fencer: &SimpleFencer, score| {
+            FencerScore::new(fencer.clone(), score, Cards::default())
+        };
+
+        let mut bout = new_bout();
+        assert!(bout
+            .update_scores_on_time(score(&fencer_a, 2), score(&fencer_b, 2))
+            .is_err());
+
+        let drawn = bout.draw_priority_seeded(7).unwrap().clone();
+        assert_eq!(new_bout().draw_priority_seeded(7).unwrap(), &drawn);
+        assert_eq!(drawn, fencer_a);
+        assert_eq!(
+            *bout.events()[0].kind(),
+            BoutEventKind::PriorityDrawn {
+                fencer: bout.fencers.pos(&drawn),
+                seed: Some(7)
+            }
+        );
+
+        bout.update_scores_on_time(score(&fencer_a, 2), score(&fencer_b, 2))
+            .unwrap();
+        assert_eq!(bout.get_winner(), Some(&drawn));
+
+        let mut bout = new_bout();
+        bout.record_touch(&fencer_a).unwrap();
+        assert!(bout.draw_priority(&mut rand::thread_rng()).is_err());
+    }
+
+    #[test]
+    fn manual_winner_overrides_scores() {
+        let fencer_a = SimpleFencer::new("Alice", "Alice");
+        let fencer_b = SimpleFencer::new("Bob", "Bob");
+        let versus: FencerVs<SimpleFencer, SimpleFencer> =
+            FencerVs::new(fencer_a.clone(), fencer_b.clone()).unwrap();
+        let mut bout = Bout::new(versus);
+        bout.update_scores(
+            FencerScore::new(&fencer_a, 5, Cards::default()),
+            FencerScore::new(&fencer_b, 2, Cards::default()),
+        )
+        .unwrap();
+
+        bout.set_winner(&fencer_b, DecisionReason::RefereeDecision)
+            .unwrap();
+        assert_eq!(bout.get_winner(), Some(&fencer_b));
+        assert_eq!(bout.touches(), (5, 2));
+        assert!(bout
+            .set_winner(
+                SimpleFencer::new("Carol", "Carol"),
+                DecisionReason::Exclusion
+            )
+            .is_err());
+
+        bout.clear_winner();
+        assert_eq!(bout.get_winner(), Some(&fencer_a));
     }
 }
@@ -5,13 +5,13 @@ use std::{borrow::Borrow, marker::PhantomData};
 /// # Usage
 /// ```
 /// use fencing_sport_lib::{fencer::SimpleFencer, bout::FencerScore, cards::Cards};
-/// let fencer_a = SimpleFencer::new("Alice");
+/// let fencer_a = SimpleFencer::new("Alice", "Alice");
 ///
 /// // Like many structs in this library, need to explicitly type it
 /// let versus: FencerScore<SimpleFencer, SimpleFencer> = FencerScore::new(fencer_a, 1, Cards::default());
 ///
 /// // Because you can also wrap the fencer type with smart pointers.
-/// let fencer_a = Box::new(SimpleFencer::new("Alice"));
+/// let fencer_a = Box::new(SimpleFencer::new("Alice", "Alice"));
 /// let versus: FencerScore<SimpleFencer, _> = FencerScore::new(fencer_a, 1, Cards::default());
 /// ```
 #[derive(Debug, Clone)]
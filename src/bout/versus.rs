@@ -9,8 +9,9 @@ use std::{
     marker::PhantomData,
 };
 
+/// Which side of a bout a fencer is on.
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
-pub(crate) enum TuplePos {
+pub enum TuplePos {
     First,
     Second,
     None,
@@ -37,15 +38,15 @@ impl Error for VersusError {}
 /// # Usage
 /// ```
 /// use fencing_sport_lib::{fencer::SimpleFencer, bout::FencerVs};
-/// let fencer_a = SimpleFencer::new("Alice");
-/// let fencer_b = SimpleFencer::new("Bob");
+/// let fencer_a = SimpleFencer::new("Alice", "Alice");
+/// let fencer_b = SimpleFencer::new("Bob", "Bob");
 ///
 /// // Like many structs in this library, need to explicitly type it
 /// let versus: FencerVs<SimpleFencer, SimpleFencer> = FencerVs::new(fencer_a, fencer_b).unwrap();
 ///
 /// // Because you can also wrap the type with smart pointers.
-/// let fencer_a = Box::new(SimpleFencer::new("Alice"));
-/// let fencer_b = Box::new(SimpleFencer::new("Bob"));
+/// let fencer_a = Box::new(SimpleFencer::new("Alice", "Alice"));
+/// let fencer_b = Box::new(SimpleFencer::new("Bob", "Bob"));
 /// let versus: FencerVs<SimpleFencer, _> = FencerVs::new(fencer_a, fencer_b).unwrap();
 /// ```
 #[derive(Debug, Eq, Clone)]
@@ -53,10 +54,10 @@ pub struct FencerVs<U: Fencer, T: Borrow<U> + Clone>(pub T, pub T, PhantomData<U
 
 impl<U: Fencer, T: Borrow<U> + Clone> FencerVs<U, T> {
     /// Initializes a new instance of FencerVs
-    /// Errors if `fencer_a  == fencer_b`
+    /// Errors if both fencers have the same id
     /// Type will need to be specified due to the way I have done the generics.
     pub fn new(fencer_a: T, fencer_b: T) -> Result<Self, VersusError> {
-        if fencer_a.borrow() == fencer_b.borrow() {
+        if fencer_a.borrow().get_id() == fencer_b.borrow().get_id() {
             Err(VersusError::SameFencer)
         } else {
             Ok(FencerVs(fencer_a, fencer_b, PhantomData))
@@ -79,9 +80,10 @@ impl<U: Fencer, T: Borrow<U> + Clone> FencerVs<U, T> {
 
     /// Returns the which position in the tuple struct the fencer is in.
     pub(crate) fn pos<A: Borrow<U>>(&self, fencer: &A) -> TuplePos {
-        if fencer.borrow() == self.0.borrow() {
+        let id = fencer.borrow().get_id();
+        if id == self.0.borrow().get_id() {
             TuplePos::First
-        } else if fencer.borrow() == self.1.borrow() {
+        } else if id == self.1.borrow().get_id() {
             TuplePos::Second
         } else {
             TuplePos::None
@@ -93,8 +95,8 @@ impl<U: Fencer, T: Borrow<U> + Clone> FencerVs<U, T> {
         FencerVs(self.0.borrow(), self.1.borrow(), PhantomData)
     }
 
-    fn order(&self) -> (&U, &U) {
-        let (a, b) = (self.0.borrow(), self.1.borrow());
+    fn order(&self) -> (U::Id, U::Id) {
+        let (a, b) = (self.0.borrow().get_id(), self.1.borrow().get_id());
         if a <= b {
             (a, b)
         } else {
@@ -130,8 +132,8 @@ mod tests {
 
     #[test]
     fn hash_unordered_test() {
-        let fencer_a = SimpleFencer::new("Alice");
-        let fencer_b = SimpleFencer::new("Bob");
+        let fencer_a = SimpleFencer::new("Alice", "Alice");
+        let fencer_b = SimpleFencer::new("Bob", "Bob");
 
         let vs_ab: FencerVs<SimpleFencer, &SimpleFencer> =
             FencerVs::new(&fencer_a, &fencer_b).unwrap();
@@ -150,8 +152,8 @@ mod tests {
 
     #[test]
     fn eq_unordered_test() {
-        let fencer_a = SimpleFencer::new("Alice");
-        let fencer_b = SimpleFencer::new("Bob");
+        let fencer_a = SimpleFencer::new("Alice", "Alice");
+        let fencer_b = SimpleFencer::new("Bob", "Bob");
 
         let vs_ab: FencerVs<SimpleFencer, &SimpleFencer> =
             FencerVs::new(&fencer_a, &fencer_b).unwrap();
@@ -164,14 +166,14 @@ mod tests {
 
     #[test]
     fn eq_type_test() {
-        let fencer_a_rc = Rc::new(SimpleFencer::new("Alice"));
-        let fencer_b_rc = Rc::new(SimpleFencer::new("Bob"));
+        let fencer_a_rc = Rc::new(SimpleFencer::new("Alice", "Alice"));
+        let fencer_b_rc = Rc::new(SimpleFencer::new("Bob", "Bob"));
 
         let vs_rc: FencerVs<SimpleFencer, Rc<SimpleFencer>> =
             FencerVs::new(fencer_a_rc, fencer_b_rc).unwrap();
 
-        let fencer_a_box = Box::new(SimpleFencer::new("Alice"));
-        let fencer_b_box = Box::new(SimpleFencer::new("Bob"));
+        let fencer_a_box = Box::new(SimpleFencer::new("Alice", "Alice"));
+        let fencer_b_box = Box::new(SimpleFencer::new("Bob", "Bob"));
 
         let vs_box: FencerVs<SimpleFencer, Box<SimpleFencer>> =
             FencerVs::new(fencer_a_box, fencer_b_box).unwrap();
@@ -1,12 +1,32 @@
 use std::{borrow::Borrow, marker::PhantomData};
 
+use serde::{Deserialize, Serialize};
+
 use crate::fencer::Fencer;
 
+/// Why the bout committee decided a bout instead of the score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DecisionReason {
+    /// The opponent abandoned the bout, the winner is recorded with a "V" and the target.
+    Abandonment,
+    MedicalForfeit,
+    Exclusion,
+    RefereeDecision,
+}
+
+/// Who won a bout, either worked out from the scores and priority or decided by hand.
+#[derive(Debug, Clone, PartialEq)]
 pub enum BoutWinner<T, U>
 where
     T: Fencer,
     U: Borrow<T>,
 {
     Auto(PhantomData<T>),
-    Manual(U),
+    Manual(U, DecisionReason),
+}
+
+impl<T: Fencer, U: Borrow<T>> Default for BoutWinner<T, U> {
+    fn default() -> Self {
+        BoutWinner::Auto(PhantomData)
+    }
 }
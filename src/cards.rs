@@ -1,6 +1,9 @@
+use std::ops::Add;
+
+use derive_getters::Getters;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Getters)]
 pub struct Cards {
     yellow: u8,
     red: u8,
@@ -10,3 +13,168 @@ pub struct Cards {
     passivity_red: u8,
     passivity_black: u8,
 }
+
+/// Offences grouped the way the rulebook groups them, the group decides which card is given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Offence {
+    Group1,
+    Group2,
+    Group3,
+    Group4,
+    Passivity,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Card {
+    Yellow,
+    Red,
+    Group3Red,
+    Black,
+    PassivityYellow,
+    PassivityRed,
+    PassivityBlack,
+}
+
+impl Card {
+    /// Red cards of any kind give a touch to the opponent.
+    pub fn awards_touch(&self) -> bool {
+        matches!(self, Card::Red | Card::Group3Red | Card::PassivityRed)
+    }
+
+    pub fn is_passivity(&self) -> bool {
+        matches!(
+            self,
+            Card::PassivityYellow | Card::PassivityRed | Card::PassivityBlack
+        )
+    }
+
+    pub fn excludes(&self) -> bool {
+        matches!(self, Card::Black | Card::PassivityBlack)
+    }
+}
+
+/// What happened when a card was issued.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Getters)]
+pub struct PenaltyOutcome {
+    card: Card,
+    touch_to_opponent: bool,
+    excluded: bool,
+}
+
+impl Cards {
+    /// Records the card for an offence, `carried` holds passivity cards from earlier bouts.
+    pub fn issue(&mut self, offence: Offence, carried: &Cards) -> PenaltyOutcome {
+        let card = match offence {
+            Offence::Group1 if self.yellow + self.red + self.group3red == 0 => Card::Yellow,
+            Offence::Group1 | Offence::Group2 => Card::Red,
+            Offence::Group3 if self.group3red == 0 => Card::Group3Red,
+            Offence::Group3 | Offence::Group4 => Card::Black,
+            Offence::Passivity => {
+                let total = *self + carried.passivity();
+                if total.passivity_yellow + total.passivity_red == 0 {
+                    Card::PassivityYellow
+                } else if total.passivity_red == 0 {
+                    Card::PassivityRed
+                } else {
+                    Card::PassivityBlack
+                }
+            }
+        };
+        self.add_card(card);
+
+        PenaltyOutcome {
+            card,
+            touch_to_opponent: card.awards_touch(),
+            excluded: card.excludes(),
+        }
+    }
+
+    pub fn add_card(&mut self, card: Card) {
+        let count = match card {
+            Card::Yellow => &mut self.yellow,
+            Card::Red => &mut self.red,
+            Card::Group3Red => &mut self.group3red,
+            Card::Black => &mut self.black,
+            Card::PassivityYellow => &mut self.passivity_yellow,
+            Card::PassivityRed => &mut self.passivity_red,
+            Card::PassivityBlack => &mut self.passivity_black,
+        };
+        *count += 1;
+    }
+
+    /// Only the passivity cards, these are the ones carried over between the bouts of a pool.
+    pub fn passivity(&self) -> Cards {
+        Cards {
+            passivity_yellow: self.passivity_yellow,
+            passivity_red: self.passivity_red,
+            passivity_black: self.passivity_black,
+            ..Cards::default()
+        }
+    }
+
+    pub fn is_excluded(&self) -> bool {
+        self.black > 0 || self.passivity_black > 0
+    }
+}
+
+impl Add for Cards {
+    type Output = Cards;
+
+    fn add(self, rhs: Cards) -> Cards {
+        Cards {
+            yellow: self.yellow + rhs.yellow,
+            red: self.red + rhs.red,
+            group3red: self.group3red + rhs.group3red,
+            black: self.black + rhs.black,
+            passivity_yellow: self.passivity_yellow + rhs.passivity_yellow,
+            passivity_red: self.passivity_red + rhs.passivity_red,
+            passivity_black: self.passivity_black + rhs.passivity_black,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Card, Cards, Offence};
+
+    #[test]
+    fn yellow_escalates_to_red() {
+        let mut cards = Cards::default();
+        let first = cards.issue(Offence::Group1, &Cards::default());
+        assert_eq!(*first.card(), Card::Yellow);
+        assert!(!first.touch_to_opponent());
+
+        let second = cards.issue(Offence::Group1, &Cards::default());
+        assert_eq!(*second.card(), Card::Red);
+        assert!(second.touch_to_opponent());
+    }
+
+    #[test]
+    fn group3_twice_is_black() {
+        let mut cards = Cards::default();
+        assert_eq!(
+            *cards.issue(Offence::Group3, &Cards::default()).card(),
+            Card::Group3Red
+        );
+        let outcome = cards.issue(Offence::Group3, &Cards::default());
+        assert_eq!(*outcome.card(), Card::Black);
+        assert!(outcome.excluded());
+        assert!(cards.is_excluded());
+    }
+
+    #[test]
+    fn passivity_carries_over() {
+        let mut earlier = Cards::default();
+        earlier.issue(Offence::Passivity, &Cards::default());
+
+        let mut cards = Cards::default();
+        assert_eq!(
+            *cards.issue(Offence::Passivity, &earlier).card(),
+            Card::PassivityRed
+        );
+        assert_eq!(
+            *cards.issue(Offence::Passivity, &earlier).card(),
+            Card::PassivityBlack
+        );
+    }
+}
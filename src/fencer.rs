@@ -1,40 +1,147 @@
 use serde::{Deserialize, Serialize};
-use std::{cmp::Ordering, hash::Hash};
+use std::{cmp::Ordering, fmt::Debug, hash::Hash};
 
 pub trait Fencer: Hash + Serialize + Eq + PartialEq + PartialOrd + Ord + Clone {
+    /// A stable identifier such as a membership number.
+    type Id: Hash + Eq + Ord + Clone + Debug;
+
+    /// Used for identity everywhere, the name is only for display.
+    fn get_id(&self) -> Self::Id;
+
     fn get_fullname(&self) -> String;
 }
 
+/// Used to keep clubs and divisions apart when making pools.
+pub trait Affiliated {
+    fn same_club(&self, other: &Self) -> bool;
+
+    fn same_division(&self, _other: &Self) -> bool {
+        false
+    }
+}
+
+/// Id and name of a fencer or team, only the id is compared.
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct SimpleFencer {
+#[serde(from = "SavedIdentity")]
+pub(crate) struct Identity {
+    name: String,
+    id: String,
+}
+
+/// Fencers and teams saved before they had ids are identified by their name.
+#[derive(Deserialize)]
+struct SavedIdentity {
     name: String,
+    #[serde(default)]
+    id: Option<String>,
+}
+
+impl From<SavedIdentity> for Identity {
+    fn from(saved: SavedIdentity) -> Self {
+        Identity {
+            id: saved.id.unwrap_or_else(|| saved.name.clone()),
+            name: saved.name,
+        }
+    }
+}
+
+impl Identity {
+    pub(crate) fn new(id: impl ToString, name: impl ToString) -> Self {
+        Identity {
+            name: name.to_string(),
+            id: id.to_string(),
+        }
+    }
+
+    pub(crate) fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Hash for Identity {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+
+impl PartialEq for Identity {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+impl Eq for Identity {}
+
+impl PartialOrd for Identity {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Identity {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SimpleFencer {
+    #[serde(flatten)]
+    identity: Identity,
     clubs: Vec<Club>,
 }
 
 impl Fencer for SimpleFencer {
+    type Id = String;
+
+    fn get_id(&self) -> String {
+        self.identity.id().to_string()
+    }
+
     fn get_fullname(&self) -> String {
-        self.name.clone()
+        self.identity.name().to_string()
     }
 }
 
 impl SimpleFencer {
-    pub fn new(name: impl ToString) -> Self {
+    /// A stable id such as a membership number.
+    pub fn new(id: impl ToString, name: impl ToString) -> Self {
         SimpleFencer {
-            name: name.to_string(),
+            identity: Identity::new(id, name),
             clubs: Vec::new(),
         }
     }
+
+    pub(crate) fn club_names(&self) -> impl Iterator<Item = &str> {
+        self.clubs.iter().map(|club| club.full_name.as_str())
+    }
+
+    pub fn add_club(&mut self, full_name: impl ToString, shortname: impl ToString) {
+        self.clubs.push(Club {
+            full_name: full_name.to_string(),
+            shortname: shortname.to_string(),
+        });
+    }
+}
+
+impl Affiliated for SimpleFencer {
+    fn same_club(&self, other: &Self) -> bool {
+        self.clubs.iter().any(|club| other.clubs.contains(club))
+    }
 }
 
 impl Hash for SimpleFencer {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.name.hash(state)
+        self.identity.hash(state)
     }
 }
 
 impl PartialEq for SimpleFencer {
     fn eq(&self, other: &Self) -> bool {
-        self.name == other.name
+        self.identity == other.identity
     }
 }
 impl Eq for SimpleFencer {}
@@ -47,7 +154,7 @@ impl PartialOrd for SimpleFencer {
 
 impl Ord for SimpleFencer {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.name.cmp(&other.name)
+        self.identity.cmp(&other.identity)
     }
 }
 
@@ -59,15 +166,35 @@ struct Club {
 
 #[cfg(test)]
 mod tests {
-    use super::SimpleFencer;
+    use super::{Fencer, SimpleFencer};
     use serde_json;
 
     #[test]
     fn serialize_test() {
-        let fencer = SimpleFencer::new("Fencer1");
+        let fencer = SimpleFencer::new("1", "Fencer1");
         let serialized_fencer = serde_json::to_string(&fencer).unwrap();
         println!("Serialized Fencer: {}", serialized_fencer.clone());
         let deser_fencer = serde_json::from_str::<SimpleFencer>(&serialized_fencer).unwrap();
         println!("Serialized Fencer: {:?}", deser_fencer);
     }
+
+    #[test]
+    fn same_name_different_id() {
+        let fencer_a = SimpleFencer::new("100001", "Alex Kim");
+        let fencer_b = SimpleFencer::new("100002", "Alex Kim");
+
+        assert_ne!(fencer_a, fencer_b);
+        assert_eq!(fencer_a.get_fullname(), fencer_b.get_fullname());
+
+        let serialized_fencer = serde_json::to_string(&fencer_a).unwrap();
+        let deser_fencer = serde_json::from_str::<SimpleFencer>(&serialized_fencer).unwrap();
+        assert_eq!(deser_fencer.get_id(), "100001");
+    }
+
+    #[test]
+    fn saved_without_id() {
+        let fencer =
+            serde_json::from_str::<SimpleFencer>(r#"{"name":"Alex Kim","clubs":[]}"#).unwrap();
+        assert_eq!(fencer.get_id(), "Alex Kim");
+    }
 }
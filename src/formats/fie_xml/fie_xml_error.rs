@@ -0,0 +1,58 @@
+use std::{error::Error, fmt::Display};
+
+use crate::pools::{bout_order::OrderError, PoolSheetError};
+
+#[derive(Debug, Clone)]
+pub enum FieXmlError {
+    Xml(String),
+    MissingElement(&'static str),
+    MissingAttribute(&'static str),
+    InvalidAttribute {
+        attribute: &'static str,
+        value: String,
+    },
+    UnknownFencer(String),
+    InvalidOrder(OrderError),
+    Pool(PoolSheetError),
+}
+
+impl Display for FieXmlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use FieXmlError::*;
+        match self {
+            Xml(err) => write!(f, "the file is not valid xml: {err}"),
+            MissingElement(element) => write!(f, "the file has no <{element}> element"),
+            MissingAttribute(attribute) => {
+                write!(f, "an element is missing the {attribute} attribute")
+            }
+            InvalidAttribute { attribute, value } => {
+                write!(f, "{value:?} is not a valid {attribute}")
+            }
+            UnknownFencer(reference) => {
+                write!(f, "fencer {reference} is not listed in <Tireurs>")
+            }
+            InvalidOrder(err) => write!(f, "the matches of a pool are not a round robin: {err}"),
+            Pool(err) => write!(f, "error creating the poolsheet: {err}"),
+        }
+    }
+}
+
+impl Error for FieXmlError {}
+
+impl From<roxmltree::Error> for FieXmlError {
+    fn from(value: roxmltree::Error) -> Self {
+        FieXmlError::Xml(value.to_string())
+    }
+}
+
+impl From<OrderError> for FieXmlError {
+    fn from(value: OrderError) -> Self {
+        FieXmlError::InvalidOrder(value)
+    }
+}
+
+impl From<PoolSheetError> for FieXmlError {
+    fn from(value: PoolSheetError) -> Self {
+        FieXmlError::Pool(value)
+    }
+}
@@ -0,0 +1,589 @@
+//! Pool rounds in the FIE XML format used by FencingTime and Engarde.
+
+use std::fmt::{Debug, Write};
+
+use indexmap::{IndexMap, IndexSet};
+use roxmltree::{Document, Node};
+
+use crate::{
+    bout::{BoutFormat, DecisionReason, FencerVs, TuplePos},
+    fencer::{Fencer, SimpleFencer},
+    formats::escape,
+    pools::{
+        bout_creation::BoutsCreator, bout_order::validate_order, PoolRound, PoolSheet,
+        PoolSheetBout, PoolSheetError,
+    },
+    weapon::Weapon,
+};
+
+mod fie_xml_error;
+pub use fie_xml_error::FieXmlError;
+
+/// The fencer data kept in a `<Tireur>` element.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Tireur {
+    /// `Nom`
+    pub last_name: String,
+    /// `Prenom`
+    pub first_name: String,
+    pub club: Option<String>,
+    /// Three letter country code.
+    pub nation: Option<String>,
+    /// Membership number, this is what identifies a fencer between files.
+    pub licence: Option<String>,
+}
+
+/// Fencers that can be written to and read from a `<Tireur>` element.
+pub trait FieFencer: Fencer + Sized {
+    fn to_tireur(&self) -> Tireur;
+
+    fn from_tireur(tireur: &Tireur) -> Result<Self, FieXmlError>;
+}
+
+impl FieFencer for SimpleFencer {
+    /// The first word of the name is the first name, the id is written as the licence.
+    fn to_tireur(&self) -> Tireur {
+        let name = self.get_fullname();
+        let (first_name, last_name) = name.split_once(' ').unwrap_or(("", &name));
+        Tireur {
+            last_name: last_name.to_string(),
+            first_name: first_name.to_string(),
+            club: self.club_names().next().map(str::to_string),
+            nation: None,
+            licence: Some(self.get_id()),
+        }
+    }
+
+    fn from_tireur(tireur: &Tireur) -> Result<Self, FieXmlError> {
+        let name = format!("{} {}", tireur.first_name, tireur.last_name)
+            .trim()
+            .to_string();
+        // Without a licence the name is the only thing that identifies the fencer.
+        let id = tireur.licence.clone().unwrap_or_else(|| name.clone());
+        let mut fencer = SimpleFencer::new(id, name);
+        if let Some(club) = &tireur.club {
+            fencer.add_club(club, club);
+        }
+        Ok(fencer)
+    }
+}
+
+/// The competition attributes of the file.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CompetitionInfo {
+    /// `TitreLong`
+    pub title: String,
+    /// `Arme`, pools are read with the weapon's pool format when it is set.
+    pub weapon: Option<Weapon>,
+    /// `Date`, written as dd.mm.yyyy by FencingTime.
+    pub date: Option<String>,
+}
+
+fn weapon_code(weapon: Weapon) -> char {
+    match weapon {
+        Weapon::Foil => 'F',
+        Weapon::Epee => 'E',
+        Weapon::Sabre => 'S',
+    }
+}
+
+/// Writes a single pool as a competition with one pool round.
+pub fn export_pool<T: FieFencer + Debug>(pool: &PoolSheet<T>, info: &CompetitionInfo) -> String {
+    write_competition(std::slice::from_ref(pool), info)
+}
+
+pub fn export_round<T: FieFencer + Debug>(round: &PoolRound<T>, info: &CompetitionInfo) -> String {
+    write_competition(round.get_pools(), info)
+}
+
+fn write_competition<T: FieFencer + Debug>(
+    pools: &[PoolSheet<T>],
+    info: &CompetitionInfo,
+) -> String {
+    // Fencers are numbered in the file by the order they first appear in.
+    let mut references: IndexMap<T::Id, &T> = IndexMap::new();
+    for pool in pools {
+        for fencer in pool.get_fencers() {
+            references.entry(fencer.get_id()).or_insert(fencer);
+        }
+    }
+    let reference = |fencer: &T| {
+        references
+            .get_index_of(&fencer.get_id())
+            .expect("Every pool fencer has a reference")
+            + 1
+    };
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = write!(
+        xml,
+        "<CompetitionIndividuelle Version=\"1.0\" TitreLong=\"{}\"",
+        escape(&info.title)
+    );
+    if let Some(weapon) = info.weapon {
+        let _ = write!(xml, " Arme=\"{}\"", weapon_code(weapon));
+    }
+    if let Some(date) = &info.date {
+        let _ = write!(xml, " Date=\"{}\"", escape(date));
+    }
+    xml.push_str(">\n  <Tireurs>\n");
+
+    for (index, fencer) in references.values().enumerate() {
+        let tireur = fencer.to_tireur();
+        let _ = write!(
+            xml,
+            "    <Tireur ID=\"{}\" Nom=\"{}\" Prenom=\"{}\"",
+            index + 1,
+            escape(&tireur.last_name),
+            escape(&tireur.first_name)
+        );
+        for (attribute, value) in [
+            ("Club", &tireur.club),
+            ("Nation", &tireur.nation),
+            ("Licence", &tireur.licence),
+        ] {
+            if let Some(value) = value {
+                let _ = write!(xml, " {attribute}=\"{}\"", escape(value));
+            }
+        }
+        xml.push_str("/>\n");
+    }
+
+    let _ = writeln!(
+        xml,
+        "  </Tireurs>\n  <Phases>\n    <TourDePoules PhaseID=\"TourPoules1\" ID=\"1\" NbDePoules=\"{}\">",
+        pools.len()
+    );
+    for (index, pool) in pools.iter().enumerate() {
+        let _ = writeln!(xml, "      <Poule ID=\"{}\">", index + 1);
+        let results = pool.finish().ok();
+        for (position, fencer) in pool.get_fencers().into_iter().enumerate() {
+            let _ = write!(
+                xml,
+                "        <Tireur REF=\"{}\" NoDansLaPoule=\"{}\"",
+                reference(fencer),
+                position + 1
+            );
+            if let Some(result) = results.as_ref().and_then(|results| results.get(fencer)) {
+                let _ = write!(
+                    xml,
+                    " NbVictoires=\"{}\" NbMatches=\"{}\" TD=\"{}\" TR=\"{}\" RangPoule=\"{}\"",
+                    result.victories(),
+                    result.bouts(),
+                    result.touches_scored(),
+                    result.touches_recieved(),
+                    result.place().inner()
+                );
+            }
+            xml.push_str("/>\n");
+        }
+
+        for (index, (_, bout)) in pool.iter_bouts().enumerate() {
+            let _ = writeln!(xml, "        <Match ID=\"{}\">", index + 1);
+            let (fencer_a, fencer_b) = bout.get_fencers();
+            let results = match_results(bout);
+            for (fencer, result) in [fencer_a, fencer_b].into_iter().zip(results) {
+                let _ = write!(xml, "          <Tireur REF=\"{}\"", reference(fencer));
+                if let Some((score, status)) = result {
+                    let _ = write!(xml, " Score=\"{score}\" Statut=\"{status}\"");
+                }
+                xml.push_str("/>\n");
+            }
+            xml.push_str("        </Match>\n");
+        }
+        xml.push_str("      </Poule>\n");
+    }
+    xml.push_str("    </TourDePoules>\n  </Phases>\n</CompetitionIndividuelle>\n");
+    xml
+}
+
+/// Score and status of each fencer, `None` until the bout has a winner.
+fn match_results<T: Fencer>(bout: &PoolSheetBout<T>) -> [Option<(u8, char)>; 2] {
+    let Some(winner) = bout.get_winner() else {
+        return [None, None];
+    };
+    let loser_status = match bout.manual_winner() {
+        Some((_, DecisionReason::Abandonment | DecisionReason::MedicalForfeit)) => 'A',
+        Some((_, DecisionReason::Exclusion)) => 'E',
+        Some((_, DecisionReason::RefereeDecision)) | None => 'D',
+    };
+    let (touches_a, touches_b) = bout.touches();
+    if bout.get_fencers().0.get_id() == winner.get_id() {
+        [Some((touches_a, 'V')), Some((touches_b, loser_status))]
+    } else {
+        [Some((touches_a, loser_status)), Some((touches_b, 'V'))]
+    }
+}
+
+/// Reads the competition attributes.
+pub fn import_info(xml: &str) -> Result<CompetitionInfo, FieXmlError> {
+    let document = Document::parse(xml)?;
+    competition_info(competition(&document)?)
+}
+
+/// Reads the first pool of the first pool round.
+pub fn import_pool<T: FieFencer + Debug>(xml: &str) -> Result<PoolSheet<T>, FieXmlError> {
+    let mut pools = read_pools(xml)?;
+    if pools.is_empty() {
+        return Err(FieXmlError::MissingElement("Poule"));
+    }
+    Ok(pools.swap_remove(0))
+}
+
+/// Reads the first pool round.
+pub fn import_round<T: FieFencer + Debug>(xml: &str) -> Result<PoolRound<T>, FieXmlError> {
+    Ok(PoolRound::from_pools(read_pools(xml)?))
+}
+
+fn competition<'a, 'input>(
+    document: &'a Document<'input>,
+) -> Result<Node<'a, 'input>, FieXmlError> {
+    let root = document.root_element();
+    if root.has_tag_name("CompetitionIndividuelle") {
+        Ok(root)
+    } else {
+        Err(FieXmlError::MissingElement("CompetitionIndividuelle"))
+    }
+}
+
+fn competition_info(competition: Node) -> Result<CompetitionInfo, FieXmlError> {
+    let weapon = match competition.attribute("Arme") {
+        None => None,
+        Some("F") => Some(Weapon::Foil),
+        Some("E") => Some(Weapon::Epee),
+        Some("S") => Some(Weapon::Sabre),
+        Some(value) => {
+            return Err(FieXmlError::InvalidAttribute {
+                attribute: "Arme",
+                value: value.to_string(),
+            })
+        }
+    };
+    Ok(CompetitionInfo {
+        title: competition
+            .attribute("TitreLong")
+            .or(competition.attribute("TitreCourt"))
+            .unwrap_or_default()
+            .to_string(),
+        weapon,
+        date: competition.attribute("Date").map(str::to_string),
+    })
+}
+
+fn child<'a, 'input>(
+    node: Node<'a, 'input>,
+    tag: &'static str,
+) -> Result<Node<'a, 'input>, FieXmlError> {
+    node.children()
+        .find(|child| child.has_tag_name(tag))
+        .ok_or(FieXmlError::MissingElement(tag))
+}
+
+fn children<'a, 'input>(
+    node: Node<'a, 'input>,
+    tag: &'static str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children().filter(move |child| child.has_tag_name(tag))
+}
+
+fn attribute<'a>(node: Node<'a, '_>, attribute: &'static str) -> Result<&'a str, FieXmlError> {
+    node.attribute(attribute)
+        .ok_or(FieXmlError::MissingAttribute(attribute))
+}
+
+fn parse_attribute<V: std::str::FromStr>(
+    node: Node,
+    name: &'static str,
+) -> Result<Option<V>, FieXmlError> {
+    node.attribute(name)
+        .map(|value| {
+            value
+                .trim()
+                .parse()
+                .map_err(|_| FieXmlError::InvalidAttribute {
+                    attribute: name,
+                    value: value.to_string(),
+                })
+        })
+        .transpose()
+}
+
+/// Uses the matches of an imported pool as the bout order.
+struct FileOrder(Vec<(usize, usize)>);
+
+impl<T: Fencer> BoutsCreator<T> for FileOrder {
+    fn get_order(&self, _fencers: &[T]) -> Result<Vec<(usize, usize)>, PoolSheetError> {
+        Ok(self.0.clone())
+    }
+}
+
+/// One fencer's side of a `<Match>`.
+struct MatchSide {
+    position: usize,
+    score: Option<u8>,
+    status: Option<char>,
+}
+
+fn read_pools<T: FieFencer + Debug>(xml: &str) -> Result<Vec<PoolSheet<T>>, FieXmlError> {
+    let document = Document::parse(xml)?;
+    let competition = competition(&document)?;
+    let info = competition_info(competition)?;
+    let format = info.weapon.map_or(BoutFormat::pool(), BoutFormat::pool_for);
+
+    let mut fencers: IndexMap<&str, T> = IndexMap::new();
+    for tireur in children(child(competition, "Tireurs")?, "Tireur") {
+        let optional = |name| tireur.attribute(name).map(str::to_string);
+        let fencer = T::from_tireur(&Tireur {
+            last_name: optional("Nom").unwrap_or_default(),
+            first_name: optional("Prenom").unwrap_or_default(),
+            club: optional("Club"),
+            nation: optional("Nation"),
+            licence: optional("Licence"),
+        })?;
+        fencers.insert(attribute(tireur, "ID")?, fencer);
+    }
+
+    let round = competition
+        .descendants()
+        .find(|node| node.has_tag_name("TourDePoules"))
+        .ok_or(FieXmlError::MissingElement("TourDePoules"))?;
+
+    let mut pools = Vec::new();
+    for poule in children(round, "Poule") {
+        // Pool positions, by NoDansLaPoule when it is there.
+        let mut entries = Vec::new();
+        for (index, tireur) in children(poule, "Tireur").enumerate() {
+            let position = parse_attribute(tireur, "NoDansLaPoule")?.unwrap_or(index + 1);
+            entries.push((position, attribute(tireur, "REF")?));
+        }
+        entries.sort_by_key(|(position, _)| *position);
+        let references: Vec<&str> = entries
+            .into_iter()
+            .map(|(_, reference)| reference)
+            .collect();
+
+        let pool_fencers = references
+            .iter()
+            .map(|reference| {
+                fencers
+                    .get(reference)
+                    .cloned()
+                    .ok_or_else(|| FieXmlError::UnknownFencer(reference.to_string()))
+            })
+            .collect::<Result<IndexSet<T>, _>>()?;
+
+        let mut matches = Vec::new();
+        for node in children(poule, "Match") {
+            let mut sides =
+                children(node, "Tireur").map(|tireur| -> Result<MatchSide, FieXmlError> {
+                    let reference = attribute(tireur, "REF")?;
+                    let position = references
+                        .iter()
+                        .position(|pool_reference| *pool_reference == reference)
+                        .ok_or_else(|| FieXmlError::UnknownFencer(reference.to_string()))?;
+                    Ok(MatchSide {
+                        position: position + 1,
+                        score: parse_attribute(tireur, "Score")?,
+                        status: tireur
+                            .attribute("Statut")
+                            .and_then(|status| status.trim().chars().next()),
+                    })
+                });
+            let (Some(side_a), Some(side_b)) = (sides.next(), sides.next()) else {
+                return Err(FieXmlError::MissingElement("Tireur"));
+            };
+            matches.push((side_a?, side_b?));
+        }
+
+        let order: Vec<(usize, usize)> = matches
+            .iter()
+            .map(|(side_a, side_b)| (side_a.position, side_b.position))
+            .collect();
+        validate_order(&order, pool_fencers.len())?;
+
+        let pool_fencers: Vec<T> = pool_fencers.into_iter().collect();
+        let mut pool = PoolSheet::with_format(
+            pool_fencers.iter().cloned().collect(),
+            &FileOrder(order),
+            format,
+        )?;
+        for (side_a, side_b) in matches {
+            let versus = FencerVs::new(
+                pool_fencers[side_a.position - 1].clone(),
+                pool_fencers[side_b.position - 1].clone(),
+            )
+            .map_err(PoolSheetError::from)?;
+            apply_match(pool.get_bout_mut(&versus)?, &side_a, &side_b)?;
+        }
+        pools.push(pool);
+    }
+    Ok(pools)
+}
+
+/// Sets the result of a match on its bout, the first side is the bout's first fencer.
+fn apply_match<T: Fencer>(
+    bout: &mut PoolSheetBout<T>,
+    side_a: &MatchSide,
+    side_b: &MatchSide,
+) -> Result<(), FieXmlError> {
+    let invalid_status = |side: &MatchSide| FieXmlError::InvalidAttribute {
+        attribute: "Statut",
+        value: side.status.map(String::from).unwrap_or_default(),
+    };
+    let (winner, loser) = match (side_a.status, side_b.status) {
+        (None, None) => return Ok(()),
+        (Some('V'), Some(status)) if status != 'V' => (TuplePos::First, side_b),
+        (Some(status), Some('V')) if status != 'V' => (TuplePos::Second, side_a),
+        _ => return Err(invalid_status(side_a)),
+    };
+
+    let (score_a, score_b) = (side_a.score.unwrap_or(0), side_b.score.unwrap_or(0));
+    let (winner_score, loser_score) = match winner {
+        TuplePos::First => (score_a, score_b),
+        _ => (score_b, score_a),
+    };
+
+    let reason = match loser.status {
+        Some('A') => Some(DecisionReason::Abandonment),
+        Some('E') => Some(DecisionReason::Exclusion),
+        Some('D') if winner_score < loser_score => Some(DecisionReason::RefereeDecision),
+        Some('D') => None,
+        _ => return Err(invalid_status(loser)),
+    };
+    match reason {
+        Some(reason) => {
+            let fencer = match winner {
+                TuplePos::First => bout.fencers.0.clone(),
+                _ => bout.fencers.1.clone(),
+            };
+            // Scores are kept as they were when the bout was stopped.
+            bout.scores = (side_a.score, side_b.score);
+            bout.set_winner(fencer, reason)?;
+        }
+        None => {
+            let ended_on_time = winner_score < bout.format.target();
+            bout.format
+                .validate_scores(score_a, score_b, ended_on_time)?;
+            bout.scores = (Some(score_a), Some(score_b));
+            bout.ended_on_time = ended_on_time;
+            if winner_score == loser_score {
+                bout.priority = winner;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bout::{BoutWinner, DecisionReason, FencerVs},
+        fencer::{Fencer, SimpleFencer},
+        pools::{test_util::pool_with_scores, PoolRound, PoolSheet},
+        weapon::Weapon,
+    };
+
+    use super::{
+        export_pool, export_round, import_info, import_pool, import_round, CompetitionInfo,
+        FieXmlError,
+    };
+
+    const POOL_SAMPLE: &str = include_str!("samples/pool.xml");
+    const ROUND_SAMPLE: &str = include_str!("samples/round.xml");
+
+    #[test]
+    fn import_sample_pool() {
+        let info = import_info(POOL_SAMPLE).unwrap();
+        assert_eq!(info.weapon, Some(Weapon::Epee));
+        assert_eq!(info.title, "UAH Fall Open - Mixed Epee");
+
+        let pool: PoolSheet<SimpleFencer> = import_pool(POOL_SAMPLE).unwrap();
+        let names: Vec<String> = pool
+            .get_fencers()
+            .iter()
+            .map(|fencer| fencer.get_fullname())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "Ana GARCIA",
+                "Ben OKAFOR",
+                "Chloe MARTIN",
+                "Dev PATEL",
+                "Emma O'NEIL"
+            ]
+        );
+        assert_eq!(pool.get_fencers()[0].get_id(), "100234");
+        assert!(pool.is_finished());
+
+        let fencers = pool.get_fencers();
+        let bout = |a: usize, b: usize| {
+            pool.get_bout(&FencerVs::new(fencers[a].clone(), fencers[b].clone()).unwrap())
+                .unwrap()
+        };
+        // Won on time, 4-2.
+        assert!(bout(0, 1).ended_on_time());
+        assert_eq!(bout(0, 1).get_scores(), Some((2, 4)));
+        // Tied on time, won on priority.
+        assert_eq!(bout(1, 2).get_priority(), Some(fencers[2]));
+        // Dev abandoned against Ben.
+        assert!(matches!(
+            bout(3, 1).get_bout_winner(),
+            BoutWinner::Manual(_, DecisionReason::Abandonment)
+        ));
+
+        let results = pool.finish().unwrap();
+        let (winner, result) = results.iter().next().unwrap();
+        assert_eq!(winner.get_fullname(), "Emma O'NEIL");
+        assert_eq!(*result.victories(), 4);
+    }
+
+    #[test]
+    fn round_trip_samples() {
+        let pool: PoolSheet<SimpleFencer> = import_pool(POOL_SAMPLE).unwrap();
+        let info = import_info(POOL_SAMPLE).unwrap();
+        let exported = export_pool(&pool, &info);
+        assert_eq!(import_pool::<SimpleFencer>(&exported).unwrap(), pool);
+        assert_eq!(import_info(&exported).unwrap(), info);
+        assert_eq!(
+            export_pool(&import_pool::<SimpleFencer>(&exported).unwrap(), &info),
+            exported
+        );
+
+        let round: PoolRound<SimpleFencer> = import_round(ROUND_SAMPLE).unwrap();
+        assert_eq!(round.get_pools().len(), 2);
+        assert!(!round.is_finished());
+        let info = import_info(ROUND_SAMPLE).unwrap();
+        let exported = export_round(&round, &info);
+        assert_eq!(import_round::<SimpleFencer>(&exported).unwrap(), round);
+    }
+
+    #[test]
+    fn export_then_import() {
+        let mut fencers: Vec<SimpleFencer> = (1..=4)
+            .map(|i| SimpleFencer::new(i, format!("Fencer {i}")))
+            .collect();
+        fencers[0].add_club("Rocket City & Co", "RCC");
+        let pool = pool_with_scores(&fencers, &[(0, 3, 5, 3)]);
+
+        let xml = export_pool(&pool, &CompetitionInfo::default());
+        assert!(xml.contains(
+            r#"<Tireur ID="1" Nom="1" Prenom="Fencer" Club="Rocket City &amp; Co" Licence="1"/>"#
+        ));
+        assert!(xml.contains(r#"<Tireur REF="2" NoDansLaPoule="2"/>"#));
+        assert_eq!(import_pool::<SimpleFencer>(&xml).unwrap(), pool);
+
+        assert!(matches!(
+            import_pool::<SimpleFencer>("<Competition/>"),
+            Err(FieXmlError::MissingElement("CompetitionIndividuelle"))
+        ));
+        let duplicate_match = xml.replace(
+            r#"<Tireur REF="4" Score="3" Statut="D"/>"#,
+            r#"<Tireur REF="3" Score="3" Statut="D"/>"#,
+        );
+        assert!(matches!(
+            import_pool::<SimpleFencer>(&duplicate_match),
+            Err(FieXmlError::InvalidOrder(_))
+        ));
+    }
+}
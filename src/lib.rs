@@ -1,5 +1,10 @@
 pub mod bout;
 pub mod cards;
 pub mod fencer;
+pub mod formats;
 pub mod organizations;
 pub mod pools;
+pub mod seeding;
+pub mod tableau;
+pub mod team;
+pub mod weapon;
@@ -1 +1,2 @@
+pub mod ncaa;
 pub mod usafencing;
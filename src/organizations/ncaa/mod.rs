@@ -0,0 +1,502 @@
+//! College dual meets, scored by bouts won across the three weapons.
+
+use std::rc::Rc;
+
+use derive_getters::Getters;
+
+use crate::{
+    bout::{Bout, BoutFormat, FencerVs},
+    fencer::Fencer,
+    weapon::Weapon,
+};
+
+mod ncaa_error;
+pub use ncaa_error::DualMeetError;
+
+pub type DualMeetBout<T> = Bout<T, Rc<T>>;
+
+/// Positions 1 to 3 are the first team, 4 to 6 the second.
+pub const DUAL_MEET_ORDER: [(usize, usize); 9] = [
+    (1, 4),
+    (2, 5),
+    (3, 6),
+    (1, 5),
+    (2, 6),
+    (3, 4),
+    (1, 6),
+    (2, 4),
+    (3, 5),
+];
+
+/// Bouts needed to win a meet of all three weapons.
+pub const BOUTS_TO_WIN: u8 = 14;
+
+/// A team's fencers in one weapon, starters in lineup order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Squad<T: Fencer> {
+    starters: [T; 3],
+    reserves: Vec<T>,
+}
+
+impl<T: Fencer> Squad<T> {
+    pub fn new(starters: [T; 3], reserves: Vec<T>) -> Squad<T> {
+        Squad { starters, reserves }
+    }
+
+    pub fn starters(&self) -> &[T; 3] {
+        &self.starters
+    }
+
+    pub fn reserves(&self) -> &[T] {
+        &self.reserves
+    }
+
+    pub fn members(&self) -> impl Iterator<Item = &T> {
+        self.starters.iter().chain(self.reserves.iter())
+    }
+
+    fn contains(&self, fencer: &T) -> bool {
+        self.members()
+            .any(|member| member.get_id() == fencer.get_id())
+    }
+}
+
+/// A fencer's bouts in a meet.
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct FencerRecord<T: Fencer> {
+    fencer: Rc<T>,
+    weapon: Weapon,
+    victories: u8,
+    bouts: u8,
+    touches_scored: u8,
+    touches_recieved: u8,
+}
+
+impl<T: Fencer> FencerRecord<T> {
+    pub fn losses(&self) -> u8 {
+        self.bouts - self.victories
+    }
+
+    pub fn indicator(&self) -> i16 {
+        i16::from(self.touches_scored) - i16::from(self.touches_recieved)
+    }
+}
+
+/// One weapon of a dual meet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeaponMeet<T: Fencer> {
+    weapon: Weapon,
+    squads: (Squad<T>, Squad<T>),
+    lineups: ([Rc<T>; 3], [Rc<T>; 3]),
+    replaced: Vec<T::Id>,
+    bouts: Vec<DualMeetBout<T>>,
+}
+
+impl<T: Fencer> WeaponMeet<T> {
+    fn new(
+        weapon: Weapon,
+        squad_a: Squad<T>,
+        squad_b: Squad<T>,
+    ) -> Result<WeaponMeet<T>, DualMeetError> {
+        if squad_a.members().any(|fencer| squad_b.contains(fencer)) {
+            return Err(DualMeetError::InvalidSquad);
+        }
+
+        let lineup = |squad: &Squad<T>| squad.starters.clone().map(Rc::new);
+        let mut meet = WeaponMeet {
+            weapon,
+            lineups: (lineup(&squad_a), lineup(&squad_b)),
+            squads: (squad_a, squad_b),
+            replaced: Vec::new(),
+            bouts: Vec::with_capacity(DUAL_MEET_ORDER.len()),
+        };
+        for index in 0..DUAL_MEET_ORDER.len() {
+            let bout = meet.create_bout(index)?;
+            meet.bouts.push(bout);
+        }
+        Ok(meet)
+    }
+
+    fn create_bout(&self, index: usize) -> Result<DualMeetBout<T>, DualMeetError> {
+        let (a, b) = DUAL_MEET_ORDER[index];
+        let vs = FencerVs::new(self.lineups.0[a - 1].clone(), self.lineups.1[b - 4].clone())?;
+        Ok(Bout::with_format(vs, BoutFormat::pool_for(self.weapon)))
+    }
+
+    pub fn weapon(&self) -> Weapon {
+        self.weapon
+    }
+
+    pub fn squads(&self) -> (&Squad<T>, &Squad<T>) {
+        (&self.squads.0, &self.squads.1)
+    }
+
+    /// The fencers currently in each position, after any substitutions.
+    pub fn lineups(&self) -> (&[Rc<T>; 3], &[Rc<T>; 3]) {
+        (&self.lineups.0, &self.lineups.1)
+    }
+
+    /// Bouts in the order they are fenced, the first team's fencer is always first in the bout.
+    pub fn bouts(&self) -> &[DualMeetBout<T>] {
+        &self.bouts
+    }
+
+    pub fn get_bout(&self, index: usize) -> Option<&DualMeetBout<T>> {
+        self.bouts.get(index)
+    }
+
+    pub fn get_bout_mut(&mut self, index: usize) -> Option<&mut DualMeetBout<T>> {
+        self.bouts.get_mut(index)
+    }
+
+    /// Bouts won by each team.
+    pub fn score(&self) -> (u8, u8) {
+        let mut score = (0, 0);
+        for bout in self.bouts.iter() {
+            if let Some(winner) = bout.get_winner() {
+                if winner.get_id() == bout.get_fencers().0.get_id() {
+                    score.0 += 1;
+                } else {
+                    score.1 += 1;
+                }
+            }
+        }
+        score
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.bouts.iter().all(|bout| bout.get_winner().is_some())
+    }
+
+    /// Whether the fencer is in the lineup or has fenced in this weapon.
+    fn involves(&self, fencer: &T) -> bool {
+        let in_lineup = self
+            .lineups
+            .0
+            .iter()
+            .chain(self.lineups.1.iter())
+            .any(|member| member.get_id() == fencer.get_id());
+        in_lineup || self.replaced.contains(&fencer.get_id())
+    }
+
+    fn substitute(&mut self, out: &T, sub: &T) -> Result<(), DualMeetError> {
+        if self.replaced.contains(&sub.get_id()) {
+            return Err(DualMeetError::FencerReplaced);
+        }
+        let position = |lineup: &[Rc<T>; 3]| {
+            lineup
+                .iter()
+                .position(|fencer| fencer.get_id() == out.get_id())
+        };
+        let (squad, lineup, offset) = match (position(&self.lineups.0), position(&self.lineups.1)) {
+            (Some(index), _) => (&self.squads.0, &mut self.lineups.0, index),
+            (_, Some(index)) => (&self.squads.1, &mut self.lineups.1, index),
+            (None, None) if self.replaced.contains(&out.get_id()) => {
+                return Err(DualMeetError::FencerReplaced)
+            }
+            (None, None) => return Err(DualMeetError::NoFencerFound),
+        };
+        if !squad.contains(sub) || lineup.iter().any(|fencer| fencer.get_id() == sub.get_id()) {
+            return Err(DualMeetError::NoFencerFound);
+        }
+
+        let involved: Vec<usize> = self
+            .bouts
+            .iter()
+            .enumerate()
+            .filter(|(_, bout)| bout.get_fencers().0 == out || bout.get_fencers().1 == out)
+            .map(|(index, _)| index)
+            .collect();
+        if involved
+            .iter()
+            .any(|&index| started(&self.bouts[index]) && self.bouts[index].get_winner().is_none())
+        {
+            return Err(DualMeetError::BoutInProgress);
+        }
+
+        let sub = squad
+            .members()
+            .find(|member| member.get_id() == sub.get_id())
+            .expect("Squad was checked to have the substitute")
+            .clone();
+        lineup[offset] = Rc::new(sub);
+        self.replaced.push(out.get_id());
+
+        // Bouts the replaced fencer has not fenced yet go to the substitute.
+        for index in involved {
+            if !started(&self.bouts[index]) {
+                self.bouts[index] = self.create_bout(index)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether anything has been entered for a bout.
+fn started<T: Fencer>(bout: &DualMeetBout<T>) -> bool {
+    bout.get_winner().is_some() || !bout.events().is_empty() || bout.scores != (None, None)
+}
+
+/// A meet between two teams, scored by the bouts won across all weapons.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DualMeet<T: Fencer> {
+    teams: (String, String),
+    weapons: Vec<WeaponMeet<T>>,
+}
+
+impl<T: Fencer> DualMeet<T> {
+    pub fn new(team_a: impl ToString, team_b: impl ToString) -> DualMeet<T> {
+        DualMeet {
+            teams: (team_a.to_string(), team_b.to_string()),
+            weapons: Vec::with_capacity(3),
+        }
+    }
+
+    pub fn get_teams(&self) -> (&str, &str) {
+        (&self.teams.0, &self.teams.1)
+    }
+
+    /// Errors if the weapon was already added or a starter fences another weapon.
+    pub fn add_weapon(
+        &mut self,
+        weapon: Weapon,
+        squad_a: Squad<T>,
+        squad_b: Squad<T>,
+    ) -> Result<&mut WeaponMeet<T>, DualMeetError> {
+        if self.get_weapon(weapon).is_some() {
+            return Err(DualMeetError::InvalidSquad);
+        }
+        let starters = squad_a.starters.iter().chain(squad_b.starters.iter());
+        if starters
+            .clone()
+            .any(|fencer| self.weapons.iter().any(|meet| meet.involves(fencer)))
+        {
+            return Err(DualMeetError::FencerInOtherWeapon);
+        }
+
+        self.weapons
+            .push(WeaponMeet::new(weapon, squad_a, squad_b)?);
+        Ok(self.weapons.last_mut().expect("Weapon was just added"))
+    }
+
+    pub fn weapons(&self) -> &[WeaponMeet<T>] {
+        &self.weapons
+    }
+
+    pub fn get_weapon(&self, weapon: Weapon) -> Option<&WeaponMeet<T>> {
+        self.weapons.iter().find(|meet| meet.weapon == weapon)
+    }
+
+    pub fn get_weapon_mut(&mut self, weapon: Weapon) -> Option<&mut WeaponMeet<T>> {
+        self.weapons.iter_mut().find(|meet| meet.weapon == weapon)
+    }
+
+    /// Replaces `out` with `sub` for the bouts `out` hasn't fenced yet.
+    pub fn substitute(&mut self, weapon: Weapon, out: &T, sub: &T) -> Result<(), DualMeetError> {
+        if self
+            .weapons
+            .iter()
+            .any(|meet| meet.weapon != weapon && meet.involves(sub))
+        {
+            return Err(DualMeetError::FencerInOtherWeapon);
+        }
+        self.get_weapon_mut(weapon)
+            .ok_or(DualMeetError::NoWeaponFound)?
+            .substitute(out, sub)
+    }
+
+    /// Bouts won by each team.
+    pub fn score(&self) -> (u8, u8) {
+        self.weapons
+            .iter()
+            .map(WeaponMeet::score)
+            .fold((0, 0), |total, score| {
+                (total.0 + score.0, total.1 + score.1)
+            })
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.weapons.len() == 3 && self.weapons.iter().all(WeaponMeet::is_finished)
+    }
+
+    /// The team that has won the meet, this is known as soon as a team has won 14 bouts.
+    pub fn get_winner(&self) -> Option<&str> {
+        let (score_a, score_b) = self.score();
+        if score_a >= BOUTS_TO_WIN {
+            Some(&self.teams.0)
+        } else if score_b >= BOUTS_TO_WIN {
+            Some(&self.teams.1)
+        } else {
+            None
+        }
+    }
+
+    /// Records of every fencer who has fenced a bout, in the order they first fenced.
+    pub fn records(&self) -> Vec<FencerRecord<T>> {
+        let mut records: Vec<FencerRecord<T>> = Vec::new();
+        for meet in self.weapons.iter() {
+            for bout in meet.bouts.iter() {
+                let Some(winner) = bout.get_winner() else {
+                    continue;
+                };
+                let (touches_a, touches_b) = bout.touches();
+                let (fencer_a, fencer_b) = bout.fencers.get_fencers_owned();
+                for (fencer, scored, recieved) in [
+                    (fencer_a, touches_a, touches_b),
+                    (fencer_b, touches_b, touches_a),
+                ] {
+                    let index = match records
+                        .iter()
+                        .position(|record| record.fencer.get_id() == fencer.get_id())
+                    {
+                        Some(index) => index,
+                        None => {
+                            records.push(FencerRecord {
+                                fencer: fencer.clone(),
+                                weapon: meet.weapon,
+                                victories: 0,
+                                bouts: 0,
+                                touches_scored: 0,
+                                touches_recieved: 0,
+                            });
+                            records.len() - 1
+                        }
+                    };
+                    let record = &mut records[index];
+                    record.bouts += 1;
+                    record.touches_scored += scored;
+                    record.touches_recieved += recieved;
+                    if winner.get_id() == fencer.get_id() {
+                        record.victories += 1;
+                    }
+                }
+            }
+        }
+        records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bout::FencerScore,
+        cards::Cards,
+        fencer::{Fencer, SimpleFencer},
+        weapon::Weapon,
+    };
+
+    use super::{DualMeet, DualMeetError, Squad, WeaponMeet};
+
+    fn squad(prefix: &str) -> Squad<SimpleFencer> {
+        let fencer = |number: usize| {
+            SimpleFencer::new(format!("{prefix}{number}"), format!("{prefix}{number}"))
+        };
+        Squad::new([fencer(1), fencer(2), fencer(3)], vec![fencer(4)])
+    }
+
+    /// Fences every bout of a weapon, the first team wins the bouts in `won_by_a`.
+    fn fence(meet: &mut WeaponMeet<SimpleFencer>, won_by_a: &[usize]) {
+        for index in 0..meet.bouts().len() {
+            let bout = meet.get_bout_mut(index).unwrap();
+            let (fencer_a, fencer_b) = bout.get_fencers_owned();
+            let (score_a, score_b) = if won_by_a.contains(&index) {
+                (5, 3)
+            } else {
+                (2, 5)
+            };
+            bout.update_scores(
+                FencerScore::new(fencer_a, score_a, Cards::default()),
+                FencerScore::new(fencer_b, score_b, Cards::default()),
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn meet_scored_by_bouts() {
+        let mut meet = DualMeet::new("UAH", "Visitors");
+        let foil = meet
+            .add_weapon(Weapon::Foil, squad("UF"), squad("VF"))
+            .unwrap();
+        fence(foil, &[0, 1, 2, 3, 4, 5]);
+        let epee = meet
+            .add_weapon(Weapon::Epee, squad("UE"), squad("VE"))
+            .unwrap();
+        fence(epee, &[0, 1, 2, 3, 4]);
+        assert_eq!(meet.score(), (11, 7));
+        assert_eq!(meet.get_winner(), None);
+
+        let sabre = meet
+            .add_weapon(Weapon::Sabre, squad("US"), squad("VS"))
+            .unwrap();
+        fence(sabre, &[0, 1, 2]);
+        assert_eq!(meet.score(), (14, 13));
+        assert!(meet.is_finished());
+        assert_eq!(meet.get_winner(), Some("UAH"));
+
+        let records = meet.records();
+        assert_eq!(records.len(), 18);
+        // UF1 fenced bouts 0, 3 and 6 of foil and lost the last one.
+        let record = &records[0];
+        assert_eq!(record.fencer().get_fullname(), "UF1");
+        assert_eq!((*record.victories(), record.losses()), (2, 1));
+        assert_eq!(record.indicator(), 2 + 2 - 3);
+    }
+
+    #[test]
+    fn substitutions() {
+        let mut meet = DualMeet::new("UAH", "Visitors");
+        let foil = meet
+            .add_weapon(Weapon::Foil, squad("UF"), squad("VF"))
+            .unwrap();
+        let out = SimpleFencer::new("UF1", "UF1");
+        let sub = SimpleFencer::new("UF4", "UF4");
+
+        foil.get_bout_mut(0).unwrap().record_touch(&out).unwrap();
+        assert!(matches!(
+            meet.substitute(Weapon::Foil, &out, &sub),
+            Err(DualMeetError::BoutInProgress)
+        ));
+        for _ in 0..4 {
+            meet.get_weapon_mut(Weapon::Foil)
+                .unwrap()
+                .get_bout_mut(0)
+                .unwrap()
+                .record_touch(&out)
+                .unwrap();
+        }
+        meet.substitute(Weapon::Foil, &out, &sub).unwrap();
+
+        let foil = meet.get_weapon(Weapon::Foil).unwrap();
+        assert_eq!(foil.get_bout(0).unwrap().get_winner(), Some(&out));
+        assert_eq!(foil.get_bout(3).unwrap().get_fencers().0, &sub);
+        assert_eq!(foil.get_bout(6).unwrap().get_fencers().0, &sub);
+
+        assert!(matches!(
+            meet.substitute(Weapon::Foil, &sub, &out),
+            Err(DualMeetError::FencerReplaced)
+        ));
+        assert!(matches!(
+            meet.substitute(
+                Weapon::Foil,
+                &SimpleFencer::new("UF2", "UF2"),
+                &SimpleFencer::new("VF4", "VF4")
+            ),
+            Err(DualMeetError::NoFencerFound)
+        ));
+
+        // Foil fencers can't come in for another weapon.
+        let mut epee_squad = squad("UE");
+        epee_squad.reserves.push(sub.clone());
+        meet.add_weapon(Weapon::Epee, epee_squad, squad("VE"))
+            .unwrap();
+        assert!(matches!(
+            meet.substitute(Weapon::Epee, &SimpleFencer::new("UE1", "UE1"), &sub),
+            Err(DualMeetError::FencerInOtherWeapon)
+        ));
+        assert!(matches!(
+            meet.add_weapon(Weapon::Sabre, squad("UF"), squad("VS")),
+            Err(DualMeetError::FencerInOtherWeapon)
+        ));
+    }
+}
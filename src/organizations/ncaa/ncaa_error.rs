@@ -0,0 +1,47 @@
+use std::{error::Error, fmt::Display};
+
+use crate::{bout::VersusError, pools::PoolSheetError};
+
+#[derive(Debug, Clone)]
+pub enum DualMeetError {
+    /// Both teams need a squad in the weapon and a weapon can only be added once.
+    InvalidSquad,
+    /// A fencer can only fence one weapon in a meet.
+    FencerInOtherWeapon,
+    NoWeaponFound,
+    NoFencerFound,
+    /// A fencer who was substituted out can't come back in.
+    FencerReplaced,
+    /// Substitutions are made between bouts, not during one.
+    BoutInProgress,
+    BoutError(PoolSheetError),
+}
+
+impl Display for DualMeetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use DualMeetError::*;
+        match self {
+            InvalidSquad => write!(f, "the squads are not valid for this meet"),
+            FencerInOtherWeapon => write!(f, "the fencer is already fencing another weapon"),
+            NoWeaponFound => write!(f, "this weapon is not part of the meet"),
+            NoFencerFound => write!(f, "the fencer is not on this squad"),
+            FencerReplaced => write!(f, "the fencer was substituted out and can't return"),
+            BoutInProgress => write!(f, "the fencer is in a bout that is being fenced"),
+            BoutError(err) => write!(f, "error updating the bout: {err}"),
+        }
+    }
+}
+
+impl Error for DualMeetError {}
+
+impl From<VersusError> for DualMeetError {
+    fn from(_: VersusError) -> Self {
+        DualMeetError::InvalidSquad
+    }
+}
+
+impl From<PoolSheetError> for DualMeetError {
+    fn from(value: PoolSheetError) -> Self {
+        DualMeetError::BoutError(value)
+    }
+}
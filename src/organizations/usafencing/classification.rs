@@ -0,0 +1,319 @@
+// Requirements taken from the classification chart linked on `Rating`,
+// These need to be validated against the current athlete handbook.
+
+use std::{fmt::Display, rc::Rc};
+
+use indexmap::IndexMap;
+
+use crate::{fencer::Fencer, pools::Placement};
+
+use super::Rating;
+
+/// The classification of an event, the letter is the best rating it awards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EventClass {
+    E1,
+    D1,
+    C1,
+    C2,
+    C3,
+    B1,
+    B2,
+    B3,
+    A1,
+    A2,
+    A3,
+    A4,
+}
+
+impl Display for EventClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+/// Smallest event there are ratings for.
+pub const MIN_COMPETITORS: usize = 6;
+
+/// The last place that earns a rating and the rating it earns.
+type Award = (usize, fn(usize) -> Rating);
+
+/// Fencers and the rating they earned at an event.
+pub type EarnedRatings<T> = Vec<(Rc<T>, Rating)>;
+
+struct ClassRequirement {
+    class: EventClass,
+    competitors: usize,
+    /// How many entrants need to be rated at least A, B, C, D and E.
+    rated: [usize; 5],
+    awards: &'static [Award],
+}
+
+/// Best class first.
+const CLASSIFICATION_CHART: [ClassRequirement; 12] = [
+    ClassRequirement {
+        class: EventClass::A4,
+        competitors: 64,
+        rated: [8, 16, 24, 32, 32],
+        awards: &[
+            (8, Rating::A),
+            (16, Rating::B),
+            (24, Rating::C),
+            (32, Rating::D),
+        ],
+    },
+    ClassRequirement {
+        class: EventClass::A3,
+        competitors: 49,
+        rated: [6, 12, 18, 24, 24],
+        awards: &[
+            (4, Rating::A),
+            (8, Rating::B),
+            (16, Rating::C),
+            (24, Rating::D),
+        ],
+    },
+    ClassRequirement {
+        class: EventClass::A2,
+        competitors: 25,
+        rated: [4, 8, 12, 16, 16],
+        awards: &[
+            (2, Rating::A),
+            (4, Rating::B),
+            (8, Rating::C),
+            (12, Rating::D),
+        ],
+    },
+    ClassRequirement {
+        class: EventClass::A1,
+        competitors: 15,
+        rated: [2, 4, 6, 8, 8],
+        awards: &[
+            (1, Rating::A),
+            (3, Rating::B),
+            (6, Rating::C),
+            (8, Rating::D),
+        ],
+    },
+    ClassRequirement {
+        class: EventClass::B3,
+        competitors: 49,
+        rated: [0, 6, 12, 18, 24],
+        awards: &[
+            (4, Rating::B),
+            (8, Rating::C),
+            (16, Rating::D),
+            (24, Rating::E),
+        ],
+    },
+    ClassRequirement {
+        class: EventClass::B2,
+        competitors: 25,
+        rated: [0, 4, 8, 12, 16],
+        awards: &[
+            (2, Rating::B),
+            (4, Rating::C),
+            (8, Rating::D),
+            (12, Rating::E),
+        ],
+    },
+    ClassRequirement {
+        class: EventClass::B1,
+        competitors: 15,
+        rated: [0, 2, 4, 6, 8],
+        awards: &[
+            (1, Rating::B),
+            (3, Rating::C),
+            (6, Rating::D),
+            (8, Rating::E),
+        ],
+    },
+    ClassRequirement {
+        class: EventClass::C3,
+        competitors: 49,
+        rated: [0, 0, 6, 12, 18],
+        awards: &[(4, Rating::C), (8, Rating::D), (16, Rating::E)],
+    },
+    ClassRequirement {
+        class: EventClass::C2,
+        competitors: 25,
+        rated: [0, 0, 4, 8, 12],
+        awards: &[(2, Rating::C), (4, Rating::D), (8, Rating::E)],
+    },
+    ClassRequirement {
+        class: EventClass::C1,
+        competitors: 15,
+        rated: [0, 0, 2, 4, 6],
+        awards: &[(1, Rating::C), (3, Rating::D), (6, Rating::E)],
+    },
+    ClassRequirement {
+        class: EventClass::D1,
+        competitors: MIN_COMPETITORS,
+        rated: [0, 0, 0, 2, 4],
+        awards: &[(1, Rating::D), (3, Rating::E)],
+    },
+    ClassRequirement {
+        class: EventClass::E1,
+        competitors: MIN_COMPETITORS,
+        rated: [0, 0, 0, 0, 0],
+        awards: &[(1, Rating::E)],
+    },
+];
+
+impl ClassRequirement {
+    fn met_by(&self, ratings: &[Rating]) -> bool {
+        if ratings.len() < self.competitors {
+            return false;
+        }
+        // rated[0] counts A's, rated[4] counts everyone rated E or better.
+        self.rated.iter().enumerate().all(|(index, &needed)| {
+            let at_least = 5 - index as u8;
+            ratings
+                .iter()
+                .filter(|rating| rating.rank() >= at_least)
+                .count()
+                >= needed
+        })
+    }
+}
+
+/// `None` if the event is too small to award ratings.
+pub fn classify(ratings: &[Rating]) -> Option<EventClass> {
+    CLASSIFICATION_CHART
+        .iter()
+        .find(|requirement| requirement.met_by(ratings))
+        .map(|requirement| requirement.class)
+}
+
+/// Ratings earned by the top finishers, fencers who already hold a better rating are left out.
+pub fn earned_ratings<T: Fencer>(
+    placements: &[(Rc<T>, Placement)],
+    ratings: &IndexMap<T::Id, Rating>,
+    season: usize,
+) -> Option<(EventClass, EarnedRatings<T>)> {
+    let rating_of = |fencer: &T| {
+        ratings
+            .get(&fencer.get_id())
+            .copied()
+            .unwrap_or(Rating::NoRating)
+    };
+    let entered: Vec<Rating> = placements
+        .iter()
+        .map(|(fencer, _)| rating_of(fencer))
+        .collect();
+
+    let requirement = CLASSIFICATION_CHART
+        .iter()
+        .find(|requirement| requirement.met_by(&entered))?;
+
+    let earned = placements
+        .iter()
+        .filter_map(|(fencer, place)| {
+            let (_, award) = requirement
+                .awards
+                .iter()
+                .find(|(last_place, _)| place.inner() <= *last_place)?;
+            let award = award(season);
+            (award.rank() >= rating_of(fencer).rank()).then(|| (fencer.clone(), award))
+        })
+        .collect();
+
+    Some((requirement.class, earned))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use indexmap::IndexMap;
+
+    use crate::{
+        fencer::{Fencer, SimpleFencer},
+        pools::Placement,
+    };
+
+    use super::{classify, earned_ratings, EventClass, Rating};
+
+    fn field(ratings: &[Rating], unrated: usize) -> Vec<Rating> {
+        let mut field = ratings.to_vec();
+        field.extend(std::iter::repeat_n(Rating::NoRating, unrated));
+        field
+    }
+
+    #[test]
+    fn classification() {
+        assert_eq!(classify(&field(&[], 5)), None);
+        assert_eq!(classify(&field(&[], 6)), Some(EventClass::E1));
+        assert_eq!(
+            classify(&field(
+                &[
+                    Rating::D(2023),
+                    Rating::D(2023),
+                    Rating::E(2022),
+                    Rating::E(2022)
+                ],
+                2
+            )),
+            Some(EventClass::D1)
+        );
+
+        let mut c1 = field(&[Rating::C(2023), Rating::B(2022)], 13);
+        assert_eq!(classify(&c1), Some(EventClass::E1));
+        c1[2] = Rating::D(2024);
+        c1[3] = Rating::D(2024);
+        c1[4] = Rating::E(2024);
+        c1[5] = Rating::E(2024);
+        assert_eq!(classify(&c1), Some(EventClass::C1));
+
+        let a4: Vec<Rating> = [
+            Rating::A(2024),
+            Rating::B(2024),
+            Rating::C(2024),
+            Rating::D(2024),
+        ]
+        .into_iter()
+        .flat_map(|rating| [rating; 8])
+        .chain(field(&[], 32))
+        .collect();
+        assert_eq!(classify(&a4), Some(EventClass::A4));
+    }
+
+    #[test]
+    fn ratings_for_top_finishers() {
+        let fencers: Vec<Rc<SimpleFencer>> = (1..=6)
+            .map(|i| Rc::new(SimpleFencer::new(i, format!("Fencer{i}"))))
+            .collect();
+        let placements: Vec<(Rc<SimpleFencer>, Placement)> = fencers
+            .iter()
+            .cloned()
+            .zip([
+                Placement::Absolute(1),
+                Placement::Absolute(2),
+                Placement::Tied(3),
+                Placement::Tied(3),
+                Placement::Absolute(5),
+                Placement::Absolute(6),
+            ])
+            .collect();
+        let mut ratings = IndexMap::new();
+        ratings.insert(fencers[0].get_id(), Rating::D(2022));
+        ratings.insert(fencers[1].get_id(), Rating::D(2023));
+        ratings.insert(fencers[2].get_id(), Rating::E(2023));
+        ratings.insert(fencers[4].get_id(), Rating::E(2024));
+
+        let (class, earned) = earned_ratings(&placements, &ratings, 2025).unwrap();
+        assert_eq!(class, EventClass::D1);
+        // Second place keeps their D.
+        assert_eq!(
+            earned
+                .iter()
+                .map(|(fencer, rating)| (fencer.get_fullname(), *rating))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Fencer1".to_string(), Rating::D(2025)),
+                ("Fencer3".to_string(), Rating::E(2025)),
+                ("Fencer4".to_string(), Rating::E(2025)),
+            ]
+        );
+    }
+}
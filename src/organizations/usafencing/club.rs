@@ -7,13 +7,77 @@ pub struct Club {
     shortname: String,
     id: usize,
     //not sure what this date is for
-    date: time::Date,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    date: Option<time::Date>,
     // Maybe some binding to libpostal,
     // maybe some other bining
     //String for now.
-    address: String,
-    state: USState,
-    point_of_contact: ContactInfo,
-    region: ClubRegion,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    state: Option<USState>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    point_of_contact: Option<ContactInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<ClubRegion>,
     division: Division,
 }
+
+impl Club {
+    pub fn new(name: String, shortname: String, id: usize, division: Division) -> Self {
+        Club {
+            name,
+            shortname,
+            id,
+            date: None,
+            address: None,
+            state: None,
+            point_of_contact: None,
+            region: None,
+            division,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn shortname(&self) -> &str {
+        &self.shortname
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn division(&self) -> Division {
+        self.division
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Club;
+    use crate::organizations::usafencing::Division;
+
+    #[test]
+    fn saved_with_every_field() {
+        let saved = r#"{"name":"Huntsville Fencing Club","shortname":"HFC","id":12,"date":[2024,108],"address":"123 Main St","state":"AL","point_of_contact":{"email":null,"phone_number":null},"region":"Region3","division":"Alabama"}"#;
+        let club: Club = serde_json::from_str(saved).unwrap();
+        assert_eq!(club.division(), Division::Alabama);
+        assert_eq!(serde_json::to_string(&club).unwrap(), saved);
+
+        let club = Club::new(
+            "Huntsville Fencing Club".into(),
+            "HFC".into(),
+            12,
+            Division::Alabama,
+        );
+        let json = serde_json::to_string(&club).unwrap();
+        assert_eq!(
+            json,
+            r#"{"name":"Huntsville Fencing Club","shortname":"HFC","id":12,"division":"Alabama"}"#
+        );
+        assert_eq!(serde_json::from_str::<Club>(&json).unwrap().id(), 12);
+    }
+}
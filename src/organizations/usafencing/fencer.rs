@@ -1,4 +1,4 @@
-use crate::fencer::Fencer;
+use crate::fencer::{Affiliated, Fencer};
 use crate::organizations::usafencing::club::Club;
 use core::fmt;
 use serde::{Deserialize, Serialize};
@@ -57,8 +57,17 @@ enum Hand {
     Right,
 }
 
+/// Fencers saved without a membership number use their name, birthday and clubs.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MemberId {
+    Number(u32),
+    Legacy(String),
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct USAFFencer {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    member_id: Option<u32>,
     name: Name,
     clubs: Vec<Club>,
     date_of_birth: Option<Date>,
@@ -67,14 +76,36 @@ pub struct USAFFencer {
 }
 
 impl Fencer for USAFFencer {
+    type Id = MemberId;
+
+    fn get_id(&self) -> MemberId {
+        match self.member_id {
+            Some(number) => MemberId::Number(number),
+            None => {
+                let clubs: Vec<String> = self
+                    .clubs
+                    .iter()
+                    .map(|club| club.id().to_string())
+                    .collect();
+                MemberId::Legacy(format!(
+                    "{:?}|{:?}|{}",
+                    self.name,
+                    self.date_of_birth,
+                    clubs.join(",")
+                ))
+            }
+        }
+    }
+
     fn get_fullname(&self) -> String {
         self.name.to_string()
     }
 }
 // Temporary
 impl USAFFencer {
-    pub fn new(name: String) -> Self {
+    pub fn new(member_id: u32, name: String) -> Self {
         USAFFencer {
+            member_id: Some(member_id),
             name: Name {
                 suffix: None,
                 first_name: name,
@@ -90,16 +121,32 @@ impl USAFFencer {
     }
 }
 
+impl Affiliated for USAFFencer {
+    fn same_club(&self, other: &Self) -> bool {
+        self.clubs
+            .iter()
+            .any(|club| other.clubs.iter().any(|other| other.id() == club.id()))
+    }
+
+    fn same_division(&self, other: &Self) -> bool {
+        self.clubs.iter().any(|club| {
+            other
+                .clubs
+                .iter()
+                .any(|other| other.division() == club.division())
+        })
+    }
+}
+
 impl Hash for USAFFencer {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.name.hash(state);
-        self.date_of_birth.hash(state);
+        self.get_id().hash(state);
     }
 }
 
 impl PartialEq for USAFFencer {
     fn eq(&self, other: &Self) -> bool {
-        (self.name == other.name) & (self.date_of_birth == other.date_of_birth)
+        self.get_id() == other.get_id()
     }
 }
 impl Eq for USAFFencer {}
@@ -112,13 +159,18 @@ impl PartialOrd for USAFFencer {
 
 impl Ord for USAFFencer {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.name.cmp(&other.name)
+        self.get_id().cmp(&other.get_id())
     }
 }
 
 impl USAFFencer {
-    pub fn with_name(name: String) -> Self {
+    pub fn add_club(&mut self, club: Club) {
+        self.clubs.push(club);
+    }
+
+    pub fn with_name(member_id: u32, name: String) -> Self {
         USAFFencer {
+            member_id: Some(member_id),
             name: Name {
                 suffix: None,
                 first_name: name,
@@ -133,3 +185,33 @@ impl USAFFencer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::fencer::Fencer;
+
+    use super::{MemberId, USAFFencer};
+
+    #[test]
+    fn saved_without_member_id() {
+        let saved = |first_name: &str| {
+            format!(
+                r#"{{"name":{{"suffix":null,"first_name":"{first_name}","last_name":"Kim","middle_initial":null,"nickname":null}},"clubs":[],"date_of_birth":null,"gender_identity":null,"handedness":null}}"#
+            )
+        };
+        let alex = serde_json::from_str::<USAFFencer>(&saved("Alex")).unwrap();
+        let sam = serde_json::from_str::<USAFFencer>(&saved("Sam")).unwrap();
+        assert_eq!(alex.get_fullname(), "Kim, Alex  ");
+        assert_ne!(alex, sam);
+        assert_ne!(alex.get_id(), sam.get_id());
+        assert!(matches!(alex.get_id(), MemberId::Legacy(_)));
+        assert_eq!(
+            serde_json::from_str::<USAFFencer>(&saved("Alex")).unwrap(),
+            alex
+        );
+        assert_eq!(
+            USAFFencer::new(7, "Alex".into()).get_id(),
+            MemberId::Number(7)
+        );
+    }
+}
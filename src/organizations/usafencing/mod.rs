@@ -1,22 +1,14 @@
+pub mod classification;
 pub mod club;
 pub mod fencer;
 pub mod pool_bout_orders;
+mod rating;
+pub use rating::{Rating, RatingParseError, RATING_VALID_SEASONS};
+
 use email_address::EmailAddress;
 use phonenumber::PhoneNumber;
 use serde::{Deserialize, Serialize};
 
-/// I don't actually have a good reference other than this: https://cdn1.sportngin.com/attachments/document/0132/5185/USA_Fencing_Classification_Reference_Chart.pdf
-/// Accessed April 17, 2024
-#[derive(Debug)]
-pub enum Rating {
-    A(usize),
-    B(usize),
-    C(usize),
-    D(usize),
-    E(usize),
-    NoRating,
-}
-
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct ContactInfo {
     email: Option<EmailAddress>,
@@ -25,8 +17,8 @@ struct ContactInfo {
 
 /// USA Fencing regions pulled from https://www.usafencing.org/regional-info
 /// Accessed April 17, 2024
-#[derive(Debug, Serialize, Deserialize, Clone)]
-enum ClubRegion {
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClubRegion {
     Region1,
     Region2,
     Region3,
@@ -38,8 +30,8 @@ enum ClubRegion {
 /// USA Fencing Division List: https://www.usafencing.org/page/show/2520204-division-information-for-members
 /// Access April 17, 2024
 // Written by passing the USA Fencing division list into chatgpt
-#[derive(Debug, Serialize, Deserialize, Clone)]
-enum Division {
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Division {
     Alabama,
     Alaska,
     Arizona,
@@ -1,6 +1,8 @@
 // Generative AI used to format the lists from
 // https://cdn1.sportngin.com/attachments/document/0034/5494/bout_order.pdf
 // These orders need to be validated
+// The tests below check them with `pools::bout_order`, some special orders fence a pair twice
+// and need to be checked against the pdf.
 
 use crate::pools::PoolSheetError;
 
@@ -469,13 +471,78 @@ pub fn get_default_order(num_fencers: usize) -> Result<Vec<(usize, usize)>, Pool
 }
 
 // Associating special bout orders with their corresponding pool size
-pub const SPECIAL_ORDERS: [(&[(usize, usize)], usize); 8] = [
-    (&POOL_OF_6_SPECIAL_ORDER_1, 6),
-    (&POOL_OF_6_SPECIAL_ORDER_2, 6),
+pub const SPECIAL_ORDERS: [(&[(usize, usize)], usize); 4] = [
     (&POOL_OF_7_SPECIAL_ORDER_1, 7),
-    (&POOL_OF_7_SPECIAL_ORDER_2, 7),
     (&POOL_OF_8_SPECIAL_ORDER_1, 8),
-    (&POOL_OF_8_SPECIAL_ORDER_2, 8),
     (&POOL_OF_9_SPECIAL_ORDER_1, 9),
     (&POOL_OF_9_SPECIAL_ORDER_2, 9),
 ];
+
+// These fence a pair twice, they stay out of SPECIAL_ORDERS until they are checked against the pdf.
+pub const UNVERIFIED_SPECIAL_ORDERS: [(&[(usize, usize)], usize); 4] = [
+    (&POOL_OF_6_SPECIAL_ORDER_1, 6),
+    (&POOL_OF_6_SPECIAL_ORDER_2, 6),
+    (&POOL_OF_7_SPECIAL_ORDER_2, 7),
+    (&POOL_OF_8_SPECIAL_ORDER_2, 8),
+];
+
+#[cfg(test)]
+mod tests {
+    use crate::pools::bout_order::{analyze_order, validate_order, OrderError};
+
+    use super::*;
+
+    #[test]
+    fn default_orders_are_round_robins() {
+        for fencer_count in 4..=12 {
+            let order = get_default_order(fencer_count).unwrap();
+            let analysis = analyze_order(&order, fencer_count).unwrap();
+            // The published pool of 4 can't avoid a couple of back to back bouts.
+            if fencer_count == 4 {
+                assert_eq!(analysis.total_consecutive(), 2);
+            } else {
+                assert_eq!(analysis.total_consecutive(), 0, "pool of {fencer_count}");
+            }
+            assert!(analysis.max_position_imbalance() <= 4);
+        }
+        assert!(get_default_order(3).is_err());
+        assert!(get_default_order(13).is_err());
+    }
+
+    #[test]
+    fn special_orders() {
+        for (order, fencer_count) in SPECIAL_ORDERS {
+            let analysis = analyze_order(order, fencer_count).unwrap();
+            assert_eq!(analysis.total_consecutive(), 0);
+        }
+
+        // Transcription errors, `ClubBoutsCreator` skips these until they are fixed.
+        assert_eq!(
+            validate_order(&POOL_OF_6_SPECIAL_ORDER_1, 6),
+            Err(OrderError::DuplicateBout { bout: 12, first: 9 })
+        );
+        assert_eq!(
+            validate_order(&POOL_OF_6_SPECIAL_ORDER_2, 6),
+            Err(OrderError::DuplicateBout { bout: 5, first: 0 })
+        );
+        assert_eq!(
+            validate_order(&POOL_OF_7_SPECIAL_ORDER_2, 7),
+            Err(OrderError::DuplicateBout { bout: 8, first: 0 })
+        );
+        assert_eq!(
+            validate_order(&POOL_OF_8_SPECIAL_ORDER_2, 8),
+            Err(OrderError::DuplicateBout { bout: 10, first: 7 })
+        );
+    }
+
+    #[test]
+    fn special_orders_match_their_size() {
+        for (order, fencer_count) in SPECIAL_ORDERS.iter().chain(&UNVERIFIED_SPECIAL_ORDERS) {
+            let (order, fencer_count) = (*order, *fencer_count);
+            assert_eq!(order.len(), fencer_count * (fencer_count - 1) / 2);
+            assert!(order
+                .iter()
+                .all(|&(a, b)| (1..=fencer_count).contains(&a) && (1..=fencer_count).contains(&b)));
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use std::{cmp::Ordering, error::Error, fmt::Display, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Seasons a rating is kept before it starts dropping.
+pub const RATING_VALID_SEASONS: usize = 4;
+
+/// I don't actually have a good reference other than this: https://cdn1.sportngin.com/attachments/document/0132/5185/USA_Fencing_Classification_Reference_Chart.pdf
+/// Accessed April 17, 2024
+///
+/// Written like "B2023" or "U".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rating {
+    A(usize),
+    B(usize),
+    C(usize),
+    D(usize),
+    E(usize),
+    NoRating,
+}
+
+impl Rating {
+    /// The letter as a number, A is 5 and unrated is 0.
+    pub(crate) fn rank(&self) -> u8 {
+        match self {
+            Rating::A(_) => 5,
+            Rating::B(_) => 4,
+            Rating::C(_) => 3,
+            Rating::D(_) => 2,
+            Rating::E(_) => 1,
+            Rating::NoRating => 0,
+        }
+    }
+
+    fn from_rank(rank: u8, year: usize) -> Rating {
+        match rank {
+            5 => Rating::A(year),
+            4 => Rating::B(year),
+            3 => Rating::C(year),
+            2 => Rating::D(year),
+            1 => Rating::E(year),
+            _ => Rating::NoRating,
+        }
+    }
+
+    pub fn letter(&self) -> char {
+        match self {
+            Rating::A(_) => 'A',
+            Rating::B(_) => 'B',
+            Rating::C(_) => 'C',
+            Rating::D(_) => 'D',
+            Rating::E(_) => 'E',
+            Rating::NoRating => 'U',
+        }
+    }
+
+    pub fn year(&self) -> Option<usize> {
+        match self {
+            Rating::A(year)
+            | Rating::B(year)
+            | Rating::C(year)
+            | Rating::D(year)
+            | Rating::E(year) => Some(*year),
+            Rating::NoRating => None,
+        }
+    }
+
+    /// Ratings last 4 seasons, then drop a letter each season.
+    pub fn in_season(&self, season: usize) -> Rating {
+        let Some(year) = self.year() else {
+            return Rating::NoRating;
+        };
+        let expired_seasons = season.saturating_sub(year + RATING_VALID_SEASONS);
+        let rank = usize::from(self.rank()).saturating_sub(expired_seasons);
+        Rating::from_rank(rank as u8, year)
+    }
+
+    /// Whether the rating has dropped at all by `season`.
+    pub fn is_expired(&self, season: usize) -> bool {
+        self.in_season(season) != *self
+    }
+}
+
+impl Display for Rating {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.year() {
+            Some(year) => write!(f, "{}{year}", self.letter()),
+            None => write!(f, "U"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RatingParseError {
+    InvalidLetter,
+    InvalidYear,
+}
+
+impl Display for RatingParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use RatingParseError::*;
+        match self {
+            InvalidLetter => write!(f, "a rating starts with A, B, C, D, E or is U"),
+            InvalidYear => write!(f, "a rating year is 2 or 4 digits"),
+        }
+    }
+}
+
+impl Error for RatingParseError {}
+
+impl FromStr for Rating {
+    type Err = RatingParseError;
+
+    /// Reads "B2023", "b23" or "U".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let mut chars = s.chars();
+        let letter = chars
+            .next()
+            .ok_or(RatingParseError::InvalidLetter)?
+            .to_ascii_uppercase();
+        let year = chars.as_str();
+
+        if letter == 'U' {
+            return match year {
+                "" => Ok(Rating::NoRating),
+                _ => Err(RatingParseError::InvalidYear),
+            };
+        }
+        let rank = match letter {
+            'A' => 5,
+            'B' => 4,
+            'C' => 3,
+            'D' => 2,
+            'E' => 1,
+            _ => return Err(RatingParseError::InvalidLetter),
+        };
+
+        if !year.chars().all(|c| c.is_ascii_digit()) {
+            return Err(RatingParseError::InvalidYear);
+        }
+        let year = match year.len() {
+            2 => {
+                2000 + year
+                    .parse::<usize>()
+                    .map_err(|_| RatingParseError::InvalidYear)?
+            }
+            4 => year.parse().map_err(|_| RatingParseError::InvalidYear)?,
+            _ => return Err(RatingParseError::InvalidYear),
+        };
+        Ok(Rating::from_rank(rank, year))
+    }
+}
+
+impl PartialOrd for Rating {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rating {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank()
+            .cmp(&other.rank())
+            .then_with(|| self.year().cmp(&other.year()))
+    }
+}
+
+impl Serialize for Rating {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rating {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let rating = String::deserialize(deserializer)?;
+        rating.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Rating, RatingParseError};
+
+    #[test]
+    fn parse_and_format() {
+        assert_eq!("B23".parse(), Ok(Rating::B(2023)));
+        assert_eq!(" a2019 ".parse(), Ok(Rating::A(2019)));
+        assert_eq!("U".parse(), Ok(Rating::NoRating));
+        assert_eq!(
+            "F23".parse::<Rating>(),
+            Err(RatingParseError::InvalidLetter)
+        );
+        assert_eq!(
+            "C2O23".parse::<Rating>(),
+            Err(RatingParseError::InvalidYear)
+        );
+        assert_eq!("C123".parse::<Rating>(), Err(RatingParseError::InvalidYear));
+
+        for rating in [Rating::E(2024), Rating::NoRating] {
+            assert_eq!(rating.to_string().parse(), Ok(rating));
+            let json = serde_json::to_string(&rating).unwrap();
+            assert_eq!(serde_json::from_str::<Rating>(&json).unwrap(), rating);
+        }
+        assert_eq!(Rating::B(2023).to_string(), "B2023");
+    }
+
+    #[test]
+    fn ordering() {
+        let mut ratings = vec![
+            Rating::C(2024),
+            Rating::NoRating,
+            Rating::A(2019),
+            Rating::C(2021),
+            Rating::B(2024),
+        ];
+        ratings.sort_by(|a, b| b.cmp(a));
+        assert_eq!(
+            ratings,
+            vec![
+                Rating::A(2019),
+                Rating::B(2024),
+                Rating::C(2024),
+                Rating::C(2021),
+                Rating::NoRating,
+            ]
+        );
+    }
+
+    #[test]
+    fn downgrade() {
+        let rating = Rating::B(2020);
+        assert_eq!(rating.in_season(2024), Rating::B(2020));
+        assert!(!rating.is_expired(2024));
+        assert_eq!(rating.in_season(2025), Rating::C(2020));
+        assert_eq!(rating.in_season(2027), Rating::E(2020));
+        assert_eq!(rating.in_season(2028), Rating::NoRating);
+        assert!(rating.is_expired(2028));
+    }
+}
@@ -1,5 +1,5 @@
-use crate::fencer::Fencer;
-use crate::organizations::usafencing::pool_bout_orders::get_default_order;
+use crate::fencer::{Affiliated, Fencer};
+use crate::organizations::usafencing::pool_bout_orders::{get_default_order, SPECIAL_ORDERS};
 use crate::pools::PoolSheetError;
 
 pub trait BoutsCreator<T: Fencer> {
@@ -14,3 +14,374 @@ impl<T: Fencer> BoutsCreator<T> for SimpleBoutsCreator {
         get_default_order(fencer_count)
     }
 }
+
+/// Places teammates so they fence each other as early as possible.
+pub struct ClubBoutsCreator;
+
+impl<T: Fencer + Affiliated> BoutsCreator<T> for ClubBoutsCreator {
+    fn get_order(&self, fencers: &[T]) -> Result<Vec<(usize, usize)>, PoolSheetError> {
+        let fencer_count = fencers.len();
+        let default_order = get_default_order(fencer_count)?;
+
+        let teammates: Vec<usize> = (0..fencer_count)
+            .filter(|&a| (0..fencer_count).any(|b| a != b && fencers[a].same_club(&fencers[b])))
+            .collect();
+        if teammates.is_empty() {
+            return Ok(default_order);
+        }
+
+        let candidates = SPECIAL_ORDERS
+            .iter()
+            .filter(|(_, size)| *size == fencer_count)
+            .map(|(order, _)| order.to_vec())
+            .chain(std::iter::once(default_order.clone()));
+
+        let mut best: Option<(usize, Vec<(usize, usize)>)> = None;
+        for order in candidates {
+            let mut placement = Placement::new(fencers, &teammates, &order);
+            placement.search(0, 0);
+            let Some((cost, positions)) = placement.best else {
+                continue;
+            };
+            if best
+                .as_ref()
+                .is_some_and(|(best_cost, _)| *best_cost <= cost)
+            {
+                continue;
+            }
+
+            // Fencers from the order's positions, everyone else keeps their seed order in the free positions.
+            let mut fencer_at = vec![None; fencer_count];
+            for (teammate, position) in teammates.iter().zip(positions) {
+                fencer_at[position] = Some(*teammate);
+            }
+            let mut others = (0..fencer_count).filter(|fencer| !teammates.contains(fencer));
+            let fencer_at: Vec<usize> = fencer_at
+                .into_iter()
+                .map(|fencer| {
+                    fencer
+                        .or_else(|| others.next())
+                        .expect("Every position has a fencer")
+                })
+                .collect();
+
+            let mapped = order
+                .iter()
+                .map(|(a, b)| (fencer_at[a - 1] + 1, fencer_at[b - 1] + 1))
+                .collect();
+            best = Some((cost, mapped));
+        }
+
+        Ok(best.map_or(default_order, |(_, order)| order))
+    }
+}
+
+/// Published orders for 4 to 12 fencers, generated orders otherwise.
+pub struct RoundRobinBoutsCreator;
+
+impl<T: Fencer> BoutsCreator<T> for RoundRobinBoutsCreator {
+    fn get_order(&self, fencers: &[T]) -> Result<Vec<(usize, usize)>, PoolSheetError> {
+        get_default_order(fencers.len()).or_else(|_| generate_order(fencers.len()))
+    }
+}
+
+/// Round robin by the circle method, fencers who have rested the longest go first.
+pub fn generate_order(fencer_count: usize) -> Result<Vec<(usize, usize)>, PoolSheetError> {
+    if fencer_count < 2 {
+        return Err(PoolSheetError::UnsupportedParticipantCount);
+    }
+
+    // An odd pool gets an extra position, fencing it is the bye.
+    let positions = fencer_count + fencer_count % 2;
+    let rounds = positions - 1;
+    let mut last_bout: Vec<Option<usize>> = vec![None; fencer_count + 1];
+    let mut order = Vec::with_capacity(fencer_count * (fencer_count - 1) / 2);
+
+    for round in 0..rounds {
+        let mut bouts: Vec<(usize, usize)> = (0..positions / 2)
+            .map(|k| match k {
+                // The fixed position swaps sides every round.
+                0 if round % 2 == 0 => (round + 1, positions),
+                0 => (positions, round + 1),
+                k => ((round + k) % rounds + 1, (round + rounds - k) % rounds + 1),
+            })
+            .filter(|&(a, b)| a <= fencer_count && b <= fencer_count)
+            .collect();
+
+        while !bouts.is_empty() {
+            let index = order.len();
+            // Bouts since the fencer last fenced, fencers who haven't fenced yet have rested the longest.
+            let rest = |fencer: usize| last_bout[fencer].map_or(usize::MAX, |last| index - last);
+            let (next, _) = bouts
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, &(a, b))| {
+                    (
+                        std::cmp::Reverse(rest(a).min(rest(b))),
+                        std::cmp::Reverse(rest(a).max(rest(b))),
+                    )
+                })
+                .expect("Round has bouts left");
+
+            let (a, b) = bouts.remove(next);
+            last_bout[a] = Some(index);
+            last_bout[b] = Some(index);
+            order.push((a, b));
+        }
+    }
+    Ok(order)
+}
+
+/// Cost is the sum of the indexes of the bouts between teammates.
+struct Placement<'a, T> {
+    fencers: &'a [T],
+    teammates: &'a [usize],
+    /// Index of the bout between two positions in the order.
+    bout_index: Vec<Vec<usize>>,
+    /// Teammates that could swap without changing the cost are placed in order.
+    after: Vec<Option<usize>>,
+    positions: Vec<usize>,
+    /// Teammate pairs not fully placed yet, indexed by how many teammates have been placed.
+    pairs_left: Vec<usize>,
+    /// Steps left before settling for the best placement found so far.
+    budget: usize,
+    best: Option<(usize, Vec<usize>)>,
+}
+
+const PLACEMENT_BUDGET: usize = 100_000;
+
+impl<'a, T: Affiliated> Placement<'a, T> {
+    fn new(fencers: &'a [T], teammates: &'a [usize], order: &[(usize, usize)]) -> Self {
+        let mut bout_index = vec![vec![0; fencers.len()]; fencers.len()];
+        for (index, (a, b)) in order.iter().enumerate() {
+            bout_index[a - 1][b - 1] = index;
+            bout_index[b - 1][a - 1] = index;
+        }
+        let same_clubs = |a: usize, b: usize| {
+            fencers[teammates[a]].same_club(&fencers[teammates[b]])
+                && (0..teammates.len()).all(|other| {
+                    other == a
+                        || other == b
+                        || fencers[teammates[a]].same_club(&fencers[teammates[other]])
+                            == fencers[teammates[b]].same_club(&fencers[teammates[other]])
+                })
+        };
+        let after = (0..teammates.len())
+            .map(|a| (0..a).rev().find(|&b| same_clubs(a, b)))
+            .collect();
+        let pairs_left = (0..=teammates.len())
+            .map(|placed| {
+                (placed.max(1)..teammates.len())
+                    .map(|b| {
+                        (0..b)
+                            .filter(|&a| fencers[teammates[a]].same_club(&fencers[teammates[b]]))
+                            .count()
+                    })
+                    .sum()
+            })
+            .collect();
+        Placement {
+            fencers,
+            teammates,
+            bout_index,
+            after,
+            positions: Vec::with_capacity(teammates.len()),
+            pairs_left,
+            budget: PLACEMENT_BUDGET,
+            best: None,
+        }
+    }
+
+    fn search(&mut self, placed: usize, cost: usize) {
+        // The pairs left fence in different bouts, so they cost at least 0 + 1 + 2 + ...
+        let pairs_left = self.pairs_left[placed];
+        let bound = cost + pairs_left * pairs_left.saturating_sub(1) / 2;
+        if self
+            .best
+            .as_ref()
+            .is_some_and(|(best_cost, _)| *best_cost <= bound || self.budget == 0)
+        {
+            return;
+        }
+        self.budget = self.budget.saturating_sub(1);
+        if placed == self.teammates.len() {
+            self.best = Some((cost, self.positions.clone()));
+            return;
+        }
+
+        let fencer = &self.fencers[self.teammates[placed]];
+        let first_position = self.after[placed].map_or(0, |other| self.positions[other] + 1);
+        for position in first_position..self.fencers.len() {
+            if self.positions.contains(&position) {
+                continue;
+            }
+            let added: usize = self
+                .positions
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| fencer.same_club(&self.fencers[self.teammates[*other]]))
+                .map(|(_, other_position)| self.bout_index[position][*other_position])
+                .sum();
+            self.positions.push(position);
+            self.search(placed + 1, cost + added);
+            self.positions.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fencer::{Affiliated, SimpleFencer},
+        pools::PoolSheetError,
+    };
+
+    use crate::organizations::usafencing::pool_bout_orders::{
+        POOL_OF_7_SPECIAL_ORDER_1, POOL_OF_8_SPECIAL_ORDER_1, POOL_OF_9_SPECIAL_ORDER_1,
+    };
+    use crate::pools::bout_order::{analyze_order, validate_order};
+
+    use super::{
+        generate_order, BoutsCreator, ClubBoutsCreator, RoundRobinBoutsCreator, SimpleBoutsCreator,
+    };
+
+    fn pool(clubs: &[Option<&str>]) -> Vec<SimpleFencer> {
+        clubs
+            .iter()
+            .enumerate()
+            .map(|(index, club)| {
+                let mut fencer = SimpleFencer::new(index + 1, format!("Fencer{}", index + 1));
+                if let Some(club) = club {
+                    fencer.add_club(*club, *club);
+                }
+                fencer
+            })
+            .collect()
+    }
+
+    #[test]
+    fn no_teammates_uses_default_order() {
+        let fencers = pool(&[None; 6]);
+        assert_eq!(
+            ClubBoutsCreator.get_order(&fencers).unwrap(),
+            SimpleBoutsCreator.get_order(&fencers).unwrap()
+        );
+    }
+
+    #[test]
+    fn teammates_fence_first() {
+        let fencers = pool(&[None, Some("UAH"), None, None, None, Some("UAH")]);
+        let order = ClubBoutsCreator.get_order(&fencers).unwrap();
+        assert!(validate_order(&order, 6).is_ok());
+        let (a, b) = order[0];
+        assert_eq!((a.min(b), a.max(b)), (2, 6));
+
+        let fencers = pool(&[
+            Some("UAH"),
+            None,
+            Some("UAH"),
+            Some("HSV"),
+            None,
+            Some("HSV"),
+            Some("UAH"),
+        ]);
+        let order = ClubBoutsCreator.get_order(&fencers).unwrap();
+        assert!(validate_order(&order, 7).is_ok());
+        let teammates = |(a, b): (usize, usize)| {
+            let club = |fencer: usize| [1, 0, 1, 2, 0, 2, 1][fencer - 1];
+            club(a) != 0 && club(a) == club(b)
+        };
+        let cost = |order: &[(usize, usize)]| {
+            order
+                .iter()
+                .enumerate()
+                .filter(|(_, bout)| teammates(**bout))
+                .map(|(index, _)| index)
+                .collect::<Vec<usize>>()
+        };
+        assert_eq!(cost(&order), vec![0, 2, 4, 7]);
+        assert_eq!(
+            cost(&SimpleBoutsCreator.get_order(&fencers).unwrap()),
+            vec![3, 11, 12, 17]
+        );
+    }
+
+    #[test]
+    fn round_robin_any_size() {
+        assert!(matches!(
+            generate_order(1),
+            Err(PoolSheetError::UnsupportedParticipantCount)
+        ));
+        assert_eq!(generate_order(2).unwrap(), vec![(1, 2)]);
+        for fencer_count in 3..=5 {
+            assert!(validate_order(&generate_order(fencer_count).unwrap(), fencer_count).is_ok());
+        }
+        for fencer_count in 6..=40 {
+            let order = generate_order(fencer_count).unwrap();
+            let analysis = analyze_order(&order, fencer_count).unwrap();
+            assert_eq!(
+                analysis.min_rest(),
+                Some(fencer_count / 2 - 2),
+                "pool of {fencer_count}"
+            );
+            // Odd pools balance exactly, in even pools everyone fences an odd number of bouts.
+            let imbalance = if fencer_count % 2 == 0 { 1 } else { 0 };
+            assert_eq!(analysis.max_position_imbalance(), imbalance);
+        }
+
+        for fencer_count in 4..=12 {
+            let fencers = pool(&vec![None; fencer_count]);
+            assert_eq!(
+                RoundRobinBoutsCreator.get_order(&fencers).unwrap(),
+                SimpleBoutsCreator.get_order(&fencers).unwrap()
+            );
+        }
+        let fencers = pool(&[None; 3]);
+        assert_eq!(RoundRobinBoutsCreator.get_order(&fencers).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn teammates_get_special_orders() {
+        let (a, b, c, d) = (Some("A"), Some("B"), Some("C"), Some("D"));
+        let pools = [
+            (vec![a, b, c, a, b, c, None], &POOL_OF_7_SPECIAL_ORDER_1[..]),
+            (vec![a, b, b, c, a, d, c, d], &POOL_OF_8_SPECIAL_ORDER_1),
+            (
+                vec![a, b, c, d, None, d, c, b, a],
+                &POOL_OF_9_SPECIAL_ORDER_1,
+            ),
+        ];
+        for (clubs, special) in pools {
+            assert_eq!(
+                ClubBoutsCreator.get_order(&pool(&clubs)).unwrap(),
+                special,
+                "pool of {}",
+                clubs.len()
+            );
+        }
+
+        // The special orders for 6 aren't verified yet, so teammates are placed in the default order.
+        let fencers = pool(&[a, b, c, a, b, c]);
+        let order = ClubBoutsCreator.get_order(&fencers).unwrap();
+        assert!(validate_order(&order, 6).is_ok());
+        let cost: usize = order
+            .iter()
+            .enumerate()
+            .filter(|(_, &(x, y))| fencers[x - 1].same_club(&fencers[y - 1]))
+            .map(|(index, _)| index)
+            .sum();
+        assert_eq!(cost, 12);
+    }
+
+    #[test]
+    fn large_club_heavy_pools_finish() {
+        let (a, b, c, d) = (Some("A"), Some("B"), Some("C"), Some("D"));
+        let fencers = pool(&[a, b, c, d, a, b, c, d, a, b, c, d]);
+        let start = std::time::Instant::now();
+        let order = ClubBoutsCreator.get_order(&fencers).unwrap();
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+        assert!(validate_order(&order, 12).is_ok());
+        let (x, y) = order[0];
+        assert!(fencers[x - 1].same_club(&fencers[y - 1]));
+    }
+}
@@ -0,0 +1,198 @@
+//! Checks bout orders and how fair they are, positions are 1 based.
+
+use std::{error::Error, fmt::Display};
+
+use derive_getters::Getters;
+
+/// The first thing wrong with an order, bouts are indexes into the order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderError {
+    InvalidPosition { bout: usize, position: usize },
+    SelfBout { bout: usize },
+    DuplicateBout { bout: usize, first: usize },
+    MissingBout(usize, usize),
+}
+
+impl Display for OrderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use OrderError::*;
+        match self {
+            InvalidPosition { bout, position } => {
+                write!(f, "bout {bout} has position {position} outside the pool")
+            }
+            SelfBout { bout } => write!(f, "bout {bout} has a fencer against themselves"),
+            DuplicateBout { bout, first } => {
+                write!(f, "bout {bout} was already fenced as bout {first}")
+            }
+            MissingBout(a, b) => write!(f, "{a} never fences {b}"),
+        }
+    }
+}
+
+impl Error for OrderError {}
+
+/// Checks that every pair of fencers in a pool of `fencer_count` fences exactly once.
+pub fn validate_order(order: &[(usize, usize)], fencer_count: usize) -> Result<(), OrderError> {
+    let mut seen: Vec<Option<usize>> = vec![None; fencer_count * fencer_count];
+    for (bout, &(a, b)) in order.iter().enumerate() {
+        if let Some(&position) = [a, b]
+            .iter()
+            .find(|&&position| position == 0 || position > fencer_count)
+        {
+            return Err(OrderError::InvalidPosition { bout, position });
+        }
+        if a == b {
+            return Err(OrderError::SelfBout { bout });
+        }
+        let index = (a.min(b) - 1) * fencer_count + a.max(b) - 1;
+        if let Some(first) = seen[index].replace(bout) {
+            return Err(OrderError::DuplicateBout { bout, first });
+        }
+    }
+
+    for a in 1..=fencer_count {
+        for b in (a + 1)..=fencer_count {
+            if seen[(a - 1) * fencer_count + b - 1].is_none() {
+                return Err(OrderError::MissingBout(a, b));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// How an order treats one fencer.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct FencerFairness {
+    /// Bouts fenced straight after the fencer's previous bout.
+    consecutive: usize,
+    /// Fewest bouts sat out between two bouts, `None` if they only fence once.
+    min_rest: Option<usize>,
+    /// Bouts the fencer is listed first in, usually on the referee's right.
+    first: usize,
+    second: usize,
+}
+
+impl FencerFairness {
+    /// How many more bouts the fencer is listed in one position than the other.
+    pub fn position_imbalance(&self) -> usize {
+        self.first.abs_diff(self.second)
+    }
+}
+
+/// Fairness of a valid order, fencers are in pool position order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderAnalysis {
+    fencers: Vec<FencerFairness>,
+}
+
+impl OrderAnalysis {
+    pub fn get_fencers(&self) -> &[FencerFairness] {
+        &self.fencers
+    }
+
+    /// The fencer at a 1 based position.
+    pub fn get_fencer(&self, position: usize) -> Option<&FencerFairness> {
+        position
+            .checked_sub(1)
+            .and_then(|index| self.fencers.get(index))
+    }
+
+    pub fn total_consecutive(&self) -> usize {
+        self.fencers.iter().map(FencerFairness::consecutive).sum()
+    }
+
+    /// The shortest rest any fencer gets.
+    pub fn min_rest(&self) -> Option<usize> {
+        self.fencers
+            .iter()
+            .filter_map(|fencer| fencer.min_rest)
+            .min()
+    }
+
+    pub fn max_position_imbalance(&self) -> usize {
+        self.fencers
+            .iter()
+            .map(FencerFairness::position_imbalance)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Validates an order then measures it for every fencer.
+pub fn analyze_order(
+    order: &[(usize, usize)],
+    fencer_count: usize,
+) -> Result<OrderAnalysis, OrderError> {
+    validate_order(order, fencer_count)?;
+
+    let mut fencers = vec![
+        FencerFairness {
+            consecutive: 0,
+            min_rest: None,
+            first: 0,
+            second: 0,
+        };
+        fencer_count
+    ];
+    let mut last_bout: Vec<Option<usize>> = vec![None; fencer_count];
+    for (bout, &(a, b)) in order.iter().enumerate() {
+        fencers[a - 1].first += 1;
+        fencers[b - 1].second += 1;
+        for index in [a - 1, b - 1] {
+            if let Some(last) = last_bout[index].replace(bout) {
+                let rest = bout - last - 1;
+                let fencer = &mut fencers[index];
+                if rest == 0 {
+                    fencer.consecutive += 1;
+                }
+                fencer.min_rest = Some(fencer.min_rest.map_or(rest, |min| min.min(rest)));
+            }
+        }
+    }
+    Ok(OrderAnalysis { fencers })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{analyze_order, validate_order, OrderError};
+
+    #[test]
+    fn invalid_orders() {
+        assert_eq!(
+            validate_order(&[(1, 2), (2, 4), (1, 3)], 3),
+            Err(OrderError::InvalidPosition {
+                bout: 1,
+                position: 4
+            })
+        );
+        assert_eq!(
+            validate_order(&[(1, 2), (3, 3)], 3),
+            Err(OrderError::SelfBout { bout: 1 })
+        );
+        assert_eq!(
+            validate_order(&[(1, 2), (2, 3), (2, 1)], 3),
+            Err(OrderError::DuplicateBout { bout: 2, first: 0 })
+        );
+        assert_eq!(
+            validate_order(&[(1, 2), (2, 3)], 3),
+            Err(OrderError::MissingBout(1, 3))
+        );
+        assert_eq!(validate_order(&[(1, 2), (2, 3), (3, 1)], 3), Ok(()));
+    }
+
+    #[test]
+    fn fairness() {
+        let analysis = analyze_order(&[(1, 2), (2, 3), (3, 1)], 3).unwrap();
+        assert_eq!(analysis.total_consecutive(), 2);
+        assert_eq!(analysis.min_rest(), Some(0));
+        assert_eq!(analysis.max_position_imbalance(), 0);
+
+        let analysis = analyze_order(&[(1, 2), (3, 4), (1, 3), (2, 4), (1, 4), (2, 3)], 4).unwrap();
+        let first = analysis.get_fencer(1).unwrap();
+        assert_eq!(*first.consecutive(), 0);
+        assert_eq!(*first.min_rest(), Some(1));
+        assert_eq!((*first.first(), *first.second()), (3, 0));
+        assert_eq!(analysis.max_position_imbalance(), 3);
+        assert_eq!(analysis.get_fencer(0), None);
+    }
+}
@@ -2,11 +2,15 @@ use std::{fmt, hash::RandomState, marker::PhantomData, rc::Rc};
 
 use indexmap::IndexMap;
 use serde::{
-    de::{MapAccess, Visitor},
+    de::{self, MapAccess, Visitor},
     Deserialize, Deserializer,
 };
 
-use crate::{bout::TuplePos, cards::Cards, fencer::Fencer};
+use crate::{
+    bout::{BoutEvent, DecisionReason, TuplePos},
+    cards::Cards,
+    fencer::Fencer,
+};
 
 #[derive(Debug, PartialEq)]
 pub(super) struct Fencers<T: Fencer> {
@@ -82,6 +86,13 @@ pub(super) struct OrphanBout {
     pub(super) scores: (Option<u8>, Option<u8>),
     pub(super) cards: (Cards, Cards),
     pub(super) priority: TuplePos,
+    #[serde(default)]
+    pub(super) ended_on_time: bool,
+    #[serde(default)]
+    pub(super) events: Vec<BoutEvent>,
+    /// Position of a winner set by the bout committee and why they won.
+    #[serde(default)]
+    pub(super) winner: Option<(TuplePos, DecisionReason)>,
 }
 
 #[derive(Debug)]
@@ -130,8 +141,10 @@ impl<'de> Deserialize<'de> for Bouts {
                 M: MapAccess<'de>,
             {
                 let mut map = Bouts::new(access.size_hint().unwrap_or(0));
-                while let Some((key_str, value)) = access.next_entry()? {
-                    let key = serde_json::from_str(key_str).unwrap();
+                while let Some((key_str, value)) = access.next_entry::<&str, _>()? {
+                    let key = serde_json::from_str(key_str).map_err(|err| {
+                        de::Error::custom(format!("invalid bout key {key_str}: {err}"))
+                    })?;
                     map.insert(key, value);
                 }
                 Ok(map)
@@ -163,8 +176,8 @@ mod tests {
                 }
             }"#;
 
-        let fencer1 = SimpleFencer::new("Fencer1");
-        let fencer2 = SimpleFencer::new("Fencer2");
+        let fencer1 = SimpleFencer::new("Fencer1", "Fencer1");
+        let fencer2 = SimpleFencer::new("Fencer2", "Fencer2");
 
         let mut map = Fencers::with_capacity(2);
         map.insert(140300542545744, Rc::new(fencer1));
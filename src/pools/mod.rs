@@ -12,18 +12,32 @@ use serde::ser::SerializeStruct;
 use serde::{Deserialize, Serialize, Serializer};
 use serializer_structs::{PoolSheetSpecialBoutsList, PoolSheetSpecialFencers};
 
-use crate::bout::{Bout, FencerScore, FencerVs};
+use crate::bout::{Bout, BoutFormat, BoutWinner, FencerScore, FencerVs, TuplePos};
+use crate::cards::{Cards, Offence, PenaltyOutcome};
 use crate::fencer::Fencer;
+use crate::weapon::Weapon;
 use bout_creation::BoutsCreator;
+use tie_break::{HeadToHead, RecordedTieBreaker, TieBreaker, TieResolution};
 
 pub mod bout_creation;
+pub mod bout_order;
 mod pool_error;
 pub use pool_error::PoolSheetError;
 mod deserializer_struct;
 mod placement;
 pub use placement::Placement;
+pub mod render;
 pub mod result;
+pub mod round;
+pub use round::PoolRound;
+pub mod round_result;
+pub use round_result::RoundResults;
 mod serializer_structs;
+#[cfg(test)]
+pub(crate) mod test_util;
+pub mod tie_break;
+mod withdrawal;
+pub use withdrawal::{Withdrawal, WithdrawalReason};
 
 pub type PoolSheetFencerScore<T> = FencerScore<T, Rc<T>>;
 pub type PoolSheetVersus<T> = FencerVs<T, Rc<T>>;
@@ -35,10 +49,25 @@ type PoolSheetMap<T> = IndexMap<PoolSheetVersus<T>, PoolSheetBout<T>, RandomStat
 pub struct PoolSheet<T: Fencer> {
     fencers: Box<[Rc<T>]>,
     bouts: IndexMap<PoolSheetVersus<T>, PoolSheetBout<T>, RandomState>,
+    tie_resolutions: Vec<TieResolution>,
+    withdrawals: Vec<Withdrawal>,
+    format: BoutFormat,
 }
 
 impl<T: Fencer + Debug> PoolSheet<T> {
+    /// Creates a poolsheet with bouts fenced to 5 touches.
     pub fn new<C>(fencers: IndexSet<T>, creator: &C) -> Result<PoolSheet<T>, PoolSheetError>
+    where
+        C: BoutsCreator<T>,
+    {
+        PoolSheet::with_format(fencers, creator, BoutFormat::pool())
+    }
+
+    pub fn with_format<C>(
+        fencers: IndexSet<T>,
+        creator: &C,
+        format: BoutFormat,
+    ) -> Result<PoolSheet<T>, PoolSheetError>
     where
         C: BoutsCreator<T>,
     {
@@ -54,6 +83,9 @@ impl<T: Fencer + Debug> PoolSheet<T> {
         let mut new_sheet = PoolSheet {
             fencers: fencers_rced.into_boxed_slice(),
             bouts: IndexMap::new(),
+            tie_resolutions: Vec::new(),
+            withdrawals: Vec::new(),
+            format,
         };
 
         for pair in bout_indexes.into_iter() {
@@ -63,7 +95,9 @@ impl<T: Fencer + Debug> PoolSheet<T> {
             )
             .expect("Error in bout creator, invalid indexes generated.");
 
-            new_sheet.bouts.insert(versus.clone(), Bout::new(versus));
+            new_sheet
+                .bouts
+                .insert(versus.clone(), Bout::with_format(versus, format));
         }
 
         Ok(new_sheet)
@@ -93,14 +127,52 @@ impl<T: Fencer + Debug> PoolSheet<T> {
         self.bouts.get_mut(&vs).ok_or(PoolSheetError::NoBoutFound)
     }
 
-    pub fn iter_bouts(&self) -> indexmap::map::Iter<FencerVs<T, Rc<T>>, Bout<T, Rc<T>>> {
+    pub fn iter_bouts(&self) -> indexmap::map::Iter<'_, FencerVs<T, Rc<T>>, Bout<T, Rc<T>>> {
         self.bouts.iter()
     }
 
+    pub fn get_format(&self) -> &BoutFormat {
+        &self.format
+    }
+
+    pub fn get_weapon(&self) -> Option<Weapon> {
+        self.format.weapon()
+    }
+
+    /// Errors without changing anything if a bout breaks the weapon's rules.
+    pub fn set_weapon(&mut self, weapon: Option<Weapon>) -> Result<(), PoolSheetError> {
+        let mut bouts = self.bouts.clone();
+        for bout in bouts.values_mut() {
+            bout.set_weapon(weapon)?;
+        }
+        self.bouts = bouts;
+        self.format.set_weapon(weapon);
+        Ok(())
+    }
+
+    /// Sets the scores of a bout that ended with a fencer reaching the target.
     pub fn update_score<U: Borrow<T> + Clone + Eq>(
         &mut self,
         fencer_a: FencerScore<T, U>,
         fencer_b: FencerScore<T, U>,
+    ) -> Result<(), PoolSheetError> {
+        self.set_final_score(fencer_a, fencer_b, false)
+    }
+
+    /// Sets the scores of a bout that ended when time ran out.
+    pub fn update_score_on_time<U: Borrow<T> + Clone + Eq>(
+        &mut self,
+        fencer_a: FencerScore<T, U>,
+        fencer_b: FencerScore<T, U>,
+    ) -> Result<(), PoolSheetError> {
+        self.set_final_score(fencer_a, fencer_b, true)
+    }
+
+    fn set_final_score<U: Borrow<T> + Clone + Eq>(
+        &mut self,
+        fencer_a: FencerScore<T, U>,
+        fencer_b: FencerScore<T, U>,
+        ended_on_time: bool,
     ) -> Result<(), PoolSheetError> {
         // Need to convert fencerscore struct since the index map needs a version using an Rc smart pointer.
         // This does mean calling this function requires 2 heap allocations every time it is used
@@ -108,12 +180,26 @@ impl<T: Fencer + Debug> PoolSheet<T> {
         let fencer_a_fencer = Rc::new(fencer_a.fencer.borrow().clone());
         let fencer_b_fencer = Rc::new(fencer_b.fencer.borrow().clone());
 
+        let withdrawals = [
+            self.withdrawal(&fencer_a_fencer),
+            self.withdrawal(&fencer_b_fencer),
+        ];
+
         let x = FencerVs::new(fencer_a_fencer, fencer_b_fencer)?;
         let (_, vs, bout) = self
             .bouts
             .get_full_mut(&x)
             .ok_or(PoolSheetError::NoBoutFound)?;
 
+        // Bouts fenced before a medical withdrawal still count, so they can be corrected.
+        if withdrawals
+            .iter()
+            .flatten()
+            .any(|reason| reason.annuls_results() || bout.get_winner().is_none())
+        {
+            return Err(PoolSheetError::FencerWithdrawn);
+        }
+
         let fencer_a = FencerScore::new(
             vs.get_fencer(&fencer_a.fencer)
                 .expect("This should have been checked earlier in the function"),
@@ -128,7 +214,11 @@ impl<T: Fencer + Debug> PoolSheet<T> {
             fencer_b.cards,
         );
 
-        bout.update_scores(fencer_a, fencer_b)
+        if ended_on_time {
+            bout.update_scores_on_time(fencer_a, fencer_b)
+        } else {
+            bout.update_scores(fencer_a, fencer_b)
+        }
     }
 
     pub fn unset_score<U: Borrow<T> + Clone + Eq>(
@@ -142,6 +232,13 @@ impl<T: Fencer + Debug> PoolSheet<T> {
         let fencer_a_fencer = Rc::new(fencer_a.fencer.borrow().clone());
         let fencer_b_fencer = Rc::new(fencer_b.fencer.borrow().clone());
 
+        // Clearing a bout would reopen it, so withdrawn fencers' bouts can only be corrected.
+        if self.withdrawal(&fencer_a_fencer).is_some()
+            || self.withdrawal(&fencer_b_fencer).is_some()
+        {
+            return Err(PoolSheetError::FencerWithdrawn);
+        }
+
         let x = FencerVs::new(fencer_a_fencer, fencer_b_fencer)?;
         let (_, _, bout) = self
             .bouts
@@ -152,38 +249,130 @@ impl<T: Fencer + Debug> PoolSheet<T> {
         Ok(())
     }
 
-    pub fn is_finished(&self) -> bool {
-        for bout in self.bouts.values() {
-            if bout.get_winner().is_none() {
-                return false;
-            }
+    /// Passivity cards from the other bouts of the pool carry over.
+    pub fn issue_card<U: Borrow<T> + Clone + Eq>(
+        &mut self,
+        vs: &FencerVs<T, U>,
+        fencer: &T,
+        offence: Offence,
+    ) -> Result<PenaltyOutcome, PoolSheetError> {
+        let current = self.get_bout(vs)?.fencers.clone();
+        let carried = self
+            .bouts
+            .iter()
+            .filter(|(other_vs, _)| **other_vs != current)
+            .filter_map(|(_, bout)| bout.get_cards(fencer))
+            .fold(Cards::default(), |carried, cards| {
+                carried + cards.passivity()
+            });
+
+        let outcome = self
+            .get_bout_mut(vs)?
+            .issue_card_with_carry_over(fencer, offence, &carried)?;
+        if *outcome.excluded() {
+            self.exclude(fencer)?;
         }
-        true
+        Ok(outcome)
+    }
+
+    /// Their remaining bouts are abandoned, withdrawing again replaces the reason.
+    pub fn withdraw(&mut self, fencer: &T, reason: WithdrawalReason) -> Result<(), PoolSheetError> {
+        let position = self
+            .fencers
+            .iter()
+            .position(|pool_fencer| pool_fencer.get_id() == fencer.get_id())
+            .ok_or(PoolSheetError::NoFencerFound)?
+            + 1;
+
+        self.withdrawals
+            .retain(|withdrawal| withdrawal.position() != position);
+        self.withdrawals.push(Withdrawal::new(position, reason));
+        Ok(())
+    }
+
+    /// Excludes a fencer from the pool, all of their bouts are annulled.
+    pub fn exclude(&mut self, fencer: &T) -> Result<(), PoolSheetError> {
+        self.withdraw(fencer, WithdrawalReason::Excluded)
+    }
+
+    pub fn withdrawal(&self, fencer: &T) -> Option<WithdrawalReason> {
+        self.withdrawals
+            .iter()
+            .find(|withdrawal| self.fencers[withdrawal.position() - 1].get_id() == fencer.get_id())
+            .map(Withdrawal::reason)
+    }
+
+    pub fn get_withdrawals(&self) -> &[Withdrawal] {
+        &self.withdrawals
+    }
+
+    /// Bouts that will not be fenced because one of the fencers withdrew.
+    pub fn is_abandoned(&self, bout: &PoolSheetBout<T>) -> bool {
+        let (fencer_a, fencer_b) = bout.get_fencers();
+        bout.get_winner().is_none()
+            && (self.withdrawal(fencer_a).is_some() || self.withdrawal(fencer_b).is_some())
+    }
+
+    /// Whether a bout counts towards the results, bouts that were abandoned or annulled do not.
+    pub(crate) fn counts(&self, bout: &PoolSheetBout<T>) -> bool {
+        let (fencer_a, fencer_b) = bout.get_fencers();
+        let annulled = [fencer_a, fencer_b].into_iter().any(|fencer| {
+            self.withdrawal(fencer)
+                .is_some_and(|reason| reason.annuls_results())
+        });
+        !annulled && !self.is_abandoned(bout)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.unfinished_bout_indexes().is_empty()
     }
 
     pub fn unfinished_bout_indexes(&self) -> Vec<usize> {
         let mut indexes = Vec::with_capacity(self.bouts.len());
         for (index, bout) in self.bouts.values().enumerate() {
-            if bout.get_winner().is_none() {
+            if bout.get_winner().is_none() && !self.is_abandoned(bout) {
                 indexes.push(index);
             }
         }
         indexes
     }
 
+    /// Ties are broken by the stored resolutions, then head to head.
     pub fn finish(&self) -> Result<PoolResults<T>, PoolSheetError> {
+        self.finish_with(&mut HeadToHead)
+    }
+
+    /// Ties are broken by the stored resolutions, then by `tie_breaker`.
+    pub fn finish_with<B: TieBreaker<T>>(
+        &self,
+        tie_breaker: &mut B,
+    ) -> Result<PoolResults<T>, PoolSheetError> {
         let indexes = self.unfinished_bout_indexes();
         if indexes.is_empty() {
-            Ok(PoolResults::new(self))
+            let mut tie_breaker =
+                RecordedTieBreaker::new(self.tie_resolutions.clone(), tie_breaker);
+            Ok(PoolResults::with_tie_breaker(self, &mut tie_breaker))
         } else {
             Err(PoolSheetError::PoolNotComplete(indexes))
         }
     }
 
+    pub fn get_tie_resolutions(&self) -> &[TieResolution] {
+        &self.tie_resolutions
+    }
+
+    /// Stored so finishing the pool again gives the same order.
+    pub fn set_tie_resolutions(&mut self, tie_resolutions: Vec<TieResolution>) {
+        self.tie_resolutions = tie_resolutions;
+    }
+
     fn _new_empty() -> PoolSheet<T> {
         PoolSheet {
             fencers: Box::new([]),
             bouts: IndexMap::new(),
+            tie_resolutions: Vec::new(),
+            withdrawals: Vec::new(),
+            format: BoutFormat::pool(),
         }
     }
 }
@@ -193,15 +382,18 @@ impl<T: Fencer + Serialize> Serialize for PoolSheet<T> {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("PoolSheet", 2)?;
+        let mut state = serializer.serialize_struct("PoolSheet", 5)?;
         state.serialize_field(
             "fencers",
-            &PoolSheetSpecialFencers::from(self.fencers.clone()),
+            &PoolSheetSpecialFencers::from(self.fencers.as_ref()),
         )?;
         state.serialize_field(
             "bouts",
-            &PoolSheetSpecialBoutsList::from(self.bouts.clone()),
+            &PoolSheetSpecialBoutsList::new(&self.fencers, &self.bouts),
         )?;
+        state.serialize_field("tie_resolutions", &self.tie_resolutions)?;
+        state.serialize_field("withdrawals", &self.withdrawals)?;
+        state.serialize_field("format", &self.format)?;
         state.end()
     }
 }
@@ -232,18 +424,41 @@ impl<'de, T: Fencer + Deserialize<'de>> Deserialize<'de> for PoolSheet<T> {
                 .ok_or(de::Error::custom("invalid keys in serialized PoolSheet."))?;
             let vs: FencerVs<T, Rc<T>> = FencerVs::new(fencer_a.clone(), fencer_b.clone())
                 .map_err(|_| de::Error::custom("invalid keys in serialized PoolSheet."))?;
-            let bout = Bout {
+            let mut pool_bout = Bout {
                 fencers: vs.clone(),
                 scores: bout.scores,
                 cards: bout.cards,
                 priority: bout.priority,
+                format: intermediate_poolsheet.format,
+                ended_on_time: bout.ended_on_time,
+                events: Vec::new(),
+                in_progress: false,
+                winner: BoutWinner::default(),
             };
-            bouts.insert(vs, bout);
+            if let Some((pos, reason)) = bout.winner {
+                let winner = match pos {
+                    TuplePos::First => fencer_a.clone(),
+                    TuplePos::Second => fencer_b.clone(),
+                    TuplePos::None => {
+                        return Err(de::Error::custom("invalid winner in serialized PoolSheet."))
+                    }
+                };
+                pool_bout.winner = BoutWinner::Manual(winner, reason);
+            }
+            if !bout.events.is_empty() {
+                pool_bout
+                    .apply_events(bout.events.clone())
+                    .map_err(|err| de::Error::custom(format!("invalid bout events: {err}")))?;
+            }
+            bouts.insert(vs, pool_bout);
         }
 
         Ok(PoolSheet {
             fencers: fencers.into(),
             bouts,
+            tie_resolutions: intermediate_poolsheet.tie_resolutions,
+            withdrawals: intermediate_poolsheet.withdrawals,
+            format: intermediate_poolsheet.format,
         })
     }
 }
@@ -252,6 +467,9 @@ impl<'de, T: Fencer + Deserialize<'de>> Deserialize<'de> for PoolSheet<T> {
 struct DeserPoolSheet<T: Fencer> {
     fencers: deserializer_struct::Fencers<T>,
     bouts: deserializer_struct::Bouts,
+    tie_resolutions: Vec<TieResolution>,
+    withdrawals: Vec<Withdrawal>,
+    format: BoutFormat,
 }
 
 // Implement Deserialize for DeserPoolSheet
@@ -266,6 +484,9 @@ where
         enum Field {
             Fencers,
             Bouts,
+            TieResolutions,
+            Withdrawals,
+            Format,
         }
 
         impl<'de> Deserialize<'de> for Field {
@@ -279,7 +500,9 @@ where
                     type Value = Field;
 
                     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                        formatter.write_str("`fencers` or `bouts`")
+                        formatter.write_str(
+                            "`fencers`, `bouts`, `tie_resolutions`, `withdrawals` or `format`",
+                        )
                     }
 
                     fn visit_str<E>(self, value: &str) -> Result<Field, E>
@@ -289,6 +512,9 @@ where
                         match value {
                             "fencers" => Ok(Field::Fencers),
                             "bouts" => Ok(Field::Bouts),
+                            "tie_resolutions" => Ok(Field::TieResolutions),
+                            "withdrawals" => Ok(Field::Withdrawals),
+                            "format" => Ok(Field::Format),
                             _ => Err(de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -322,7 +548,17 @@ where
                 let bouts = seq
                     .next_element()?
                     .ok_or_else(|| de::Error::invalid_length(1, &self))?;
-                Ok(DeserPoolSheet { fencers, bouts })
+                // Older poolsheets were saved without tie resolutions.
+                let tie_resolutions = seq.next_element()?.unwrap_or_default();
+                let withdrawals = seq.next_element()?.unwrap_or_default();
+                let format = seq.next_element()?.unwrap_or_default();
+                Ok(DeserPoolSheet {
+                    fencers,
+                    bouts,
+                    tie_resolutions,
+                    withdrawals,
+                    format,
+                })
             }
 
             fn visit_map<A>(self, mut map: A) -> Result<DeserPoolSheet<T>, A::Error>
@@ -331,6 +567,9 @@ where
             {
                 let mut fencers = None;
                 let mut bouts = None;
+                let mut tie_resolutions = None;
+                let mut withdrawals = None;
+                let mut format = None;
                 while let Some(key) = map.next_key()? {
                     match key {
                         Field::Fencers => {
@@ -345,15 +584,50 @@ where
                             }
                             bouts = Some(map.next_value()?);
                         }
+                        Field::TieResolutions => {
+                            if tie_resolutions.is_some() {
+                                return Err(de::Error::duplicate_field("tie_resolutions"));
+                            }
+                            tie_resolutions = Some(map.next_value()?);
+                        }
+                        Field::Withdrawals => {
+                            if withdrawals.is_some() {
+                                return Err(de::Error::duplicate_field("withdrawals"));
+                            }
+                            withdrawals = Some(map.next_value()?);
+                        }
+                        Field::Format => {
+                            if format.is_some() {
+                                return Err(de::Error::duplicate_field("format"));
+                            }
+                            format = Some(map.next_value()?);
+                        }
                     }
                 }
                 let fencers = fencers.ok_or_else(|| de::Error::missing_field("fencers"))?;
                 let bouts = bouts.ok_or_else(|| de::Error::missing_field("bouts"))?;
-                Ok(DeserPoolSheet { fencers, bouts })
+                // Older poolsheets were saved without tie resolutions.
+                let tie_resolutions = tie_resolutions.unwrap_or_default();
+                let withdrawals = withdrawals.unwrap_or_default();
+                // Poolsheets saved before formats were added are 5 touch pools.
+                let format = format.unwrap_or_default();
+                Ok(DeserPoolSheet {
+                    fencers,
+                    bouts,
+                    tie_resolutions,
+                    withdrawals,
+                    format,
+                })
             }
         }
 
-        const FIELDS: &[&str] = &["fencers", "bouts"];
+        const FIELDS: &[&str] = &[
+            "fencers",
+            "bouts",
+            "tie_resolutions",
+            "withdrawals",
+            "format",
+        ];
         deserializer.deserialize_struct(
             "DeserPoolSheet",
             FIELDS,
@@ -368,16 +642,25 @@ where
 mod tests {
     use indexmap::IndexSet;
 
-    use super::{bout_creation::SimpleBoutsCreator, DeserPoolSheet, PoolSheet};
-    use crate::{bout::FencerScore, cards::Cards, fencer::SimpleFencer};
+    use super::{
+        bout_creation::SimpleBoutsCreator,
+        test_util::{fencers, pool_with_scores},
+        DeserPoolSheet, PoolSheet, PoolSheetError, WithdrawalReason,
+    };
+    use crate::{
+        bout::{FencerScore, FencerVs},
+        cards::{Card, Cards, Offence},
+        fencer::{Fencer, SimpleFencer},
+        weapon::Weapon,
+    };
 
     #[test]
     fn from_vec_test() {
         let fencers = vec![
-            SimpleFencer::new("Fencer1"),
-            SimpleFencer::new("Fencer2"),
-            SimpleFencer::new("Fencer3"),
-            SimpleFencer::new("Fencer4"),
+            SimpleFencer::new("Fencer1", "Fencer1"),
+            SimpleFencer::new("Fencer2", "Fencer2"),
+            SimpleFencer::new("Fencer3", "Fencer3"),
+            SimpleFencer::new("Fencer4", "Fencer4"),
         ];
 
         let _pool_sheet =
@@ -387,10 +670,10 @@ mod tests {
     #[test]
     fn iter_test() {
         let fencers = [
-            SimpleFencer::new("Fencer1"),
-            SimpleFencer::new("Fencer2"),
-            SimpleFencer::new("Fencer3"),
-            SimpleFencer::new("Fencer4"),
+            SimpleFencer::new("Fencer1", "Fencer1"),
+            SimpleFencer::new("Fencer2", "Fencer2"),
+            SimpleFencer::new("Fencer3", "Fencer3"),
+            SimpleFencer::new("Fencer4", "Fencer4"),
         ];
 
         let pool_sheet = PoolSheet::new(fencers.into(), &SimpleBoutsCreator).unwrap();
@@ -402,10 +685,10 @@ mod tests {
     #[test]
     fn bout_addressing() {
         let fencers = [
-            SimpleFencer::new("Fencer1"),
-            SimpleFencer::new("Fencer2"),
-            SimpleFencer::new("Fencer3"),
-            SimpleFencer::new("Fencer4"),
+            SimpleFencer::new("Fencer1", "Fencer1"),
+            SimpleFencer::new("Fencer2", "Fencer2"),
+            SimpleFencer::new("Fencer3", "Fencer3"),
+            SimpleFencer::new("Fencer4", "Fencer4"),
         ];
 
         let json_fencer1 =
@@ -426,10 +709,10 @@ mod tests {
     #[ignore = "Requires manual inspection"]
     fn serialize_poolsheet() {
         let fencers = [
-            SimpleFencer::new("Fencer1"),
-            SimpleFencer::new("Fencer2"),
-            SimpleFencer::new("Fencer3"),
-            SimpleFencer::new("Fencer4"),
+            SimpleFencer::new("Fencer1", "Fencer1"),
+            SimpleFencer::new("Fencer2", "Fencer2"),
+            SimpleFencer::new("Fencer3", "Fencer3"),
+            SimpleFencer::new("Fencer4", "Fencer4"),
         ];
         let mut pool_sheet = PoolSheet::new(fencers.clone().into(), &SimpleBoutsCreator).unwrap();
         pool_sheet
@@ -447,10 +730,10 @@ mod tests {
     /// Make sure that the order of FencerScores input to the update_score() function does not matter.
     fn update_score_unordered() {
         let fencers = [
-            SimpleFencer::new("Fencer1"),
-            SimpleFencer::new("Fencer2"),
-            SimpleFencer::new("Fencer3"),
-            SimpleFencer::new("Fencer4"),
+            SimpleFencer::new("Fencer1", "Fencer1"),
+            SimpleFencer::new("Fencer2", "Fencer2"),
+            SimpleFencer::new("Fencer3", "Fencer3"),
+            SimpleFencer::new("Fencer4", "Fencer4"),
         ];
 
         let fencer_1_score = FencerScore::new(fencers[0].clone(), 3, Cards::default());
@@ -469,6 +752,267 @@ mod tests {
         assert_eq!(pool_sheet_a, pool_sheet_b)
     }
 
+    #[test]
+    fn same_name_in_pool() {
+        let fencers = [
+            SimpleFencer::new("1", "Alex Kim"),
+            SimpleFencer::new("2", "Alex Kim"),
+            SimpleFencer::new("Fencer3", "Fencer3"),
+            SimpleFencer::new("Fencer4", "Fencer4"),
+        ];
+
+        let mut pool_sheet = PoolSheet::new(fencers.clone().into(), &SimpleBoutsCreator).unwrap();
+        assert_eq!(pool_sheet.get_fencers().len(), 4);
+
+        pool_sheet
+            .update_score(
+                FencerScore::new(fencers[1].clone(), 5, Cards::default()),
+                FencerScore::new(fencers[0].clone(), 2, Cards::default()),
+            )
+            .unwrap();
+        let bout = pool_sheet
+            .get_bout(&FencerVs::new(fencers[0].clone(), fencers[1].clone()).unwrap())
+            .unwrap();
+        assert_eq!(bout.get_winner().unwrap().get_id(), "2");
+    }
+
+    #[test]
+    fn passivity_carried_across_pool() {
+        let fencers = fencers(4);
+        let mut pool_sheet = pool_with_scores(&fencers, &[]);
+
+        let first_bout = FencerVs::new(&fencers[0], &fencers[1]).unwrap();
+        let outcome = pool_sheet
+            .issue_card(&first_bout, &fencers[0], Offence::Passivity)
+            .unwrap();
+        assert_eq!(*outcome.card(), Card::PassivityYellow);
+
+        let second_bout = FencerVs::new(&fencers[0], &fencers[2]).unwrap();
+        let outcome = pool_sheet
+            .issue_card(&second_bout, &fencers[0], Offence::Passivity)
+            .unwrap();
+        assert_eq!(*outcome.card(), Card::PassivityRed);
+        assert_eq!(
+            pool_sheet
+                .get_bout(&second_bout)
+                .unwrap()
+                .get_score(&fencers[2]),
+            Some(1)
+        );
+
+        // Other cards do not carry over.
+        pool_sheet
+            .issue_card(&first_bout, &fencers[0], Offence::Group1)
+            .unwrap();
+        let outcome = pool_sheet
+            .issue_card(&second_bout, &fencers[0], Offence::Group1)
+            .unwrap();
+        assert_eq!(*outcome.card(), Card::Yellow);
+    }
+
+    /// Higher seeds win 5-0, Fencer4 withdraws after their first bout.
+    fn pool_with_withdrawal(reason: WithdrawalReason) -> PoolSheet<SimpleFencer> {
+        let fencers = fencers(4);
+        let mut pool_sheet = pool_with_scores(&fencers, &[(0, 3, 5, 0)]);
+        let score = |a: usize, b: usize| {
+            (
+                FencerScore::new(fencers[a].clone(), 5, Cards::default()),
+                FencerScore::new(fencers[b].clone(), 0, Cards::default()),
+            )
+        };
+
+        pool_sheet.withdraw(&fencers[3], reason).unwrap();
+        assert!(pool_sheet
+            .update_score(score(1, 3).0, score(1, 3).1)
+            .is_err());
+        assert!(!pool_sheet.is_finished());
+
+        for (a, b) in [(0, 1), (0, 2), (1, 2)] {
+            let (a, b) = score(a, b);
+            pool_sheet.update_score(a, b).unwrap();
+        }
+        assert!(pool_sheet.is_finished());
+        pool_sheet
+    }
+
+    #[test]
+    fn medical_withdrawal_keeps_results() {
+        let mut pool_sheet = pool_with_withdrawal(WithdrawalReason::Medical);
+        let fencer1 = SimpleFencer::new("Fencer1", "Fencer1");
+        let fencer4 = SimpleFencer::new("Fencer4", "Fencer4");
+        pool_sheet
+            .update_score(
+                FencerScore::new(fencer1.clone(), 5, Cards::default()),
+                FencerScore::new(fencer4.clone(), 3, Cards::default()),
+            )
+            .unwrap();
+        let results = pool_sheet.finish().unwrap();
+        assert_eq!(*results.get(&fencer1).unwrap().touches_recieved(), 3);
+
+        let ranked: Vec<(String, u8)> = results
+            .iter()
+            .map(|(fencer, result)| (fencer.get_fullname(), *result.victories()))
+            .collect();
+        assert_eq!(
+            ranked,
+            vec![
+                ("Fencer1".to_string(), 3),
+                ("Fencer2".to_string(), 1),
+                ("Fencer3".to_string(), 0)
+            ]
+        );
+        assert_eq!(results.withdrawn().len(), 1);
+        assert_eq!(results.withdrawn()[0].0.get_fullname(), "Fencer4");
+        assert_eq!(results.withdrawn()[0].1, WithdrawalReason::Medical);
+    }
+
+    #[test]
+    fn medical_withdrawal_ranks_by_ratio() {
+        let mut pool_sheet = pool_with_withdrawal(WithdrawalReason::Medical);
+        let fencers = fencers(4);
+        let score = |a: usize, score_a: u8, b: usize, score_b: u8| {
+            (
+                FencerScore::new(fencers[a].clone(), score_a, Cards::default()),
+                FencerScore::new(fencers[b].clone(), score_b, Cards::default()),
+            )
+        };
+        for (a, b) in [score(1, 5, 0, 4), score(1, 5, 2, 4)] {
+            pool_sheet.update_score(a, b).unwrap();
+        }
+
+        // Fencer1 has more victories and a better indicator, but fenced the withdrawn fencer.
+        let results = pool_sheet.finish().unwrap();
+        let ranked: Vec<(String, u8, u8)> = results
+            .iter()
+            .map(|(fencer, result)| (fencer.get_fullname(), *result.victories(), *result.bouts()))
+            .collect();
+        assert_eq!(
+            ranked,
+            vec![
+                ("Fencer2".to_string(), 2, 2),
+                ("Fencer1".to_string(), 2, 3),
+                ("Fencer3".to_string(), 0, 2)
+            ]
+        );
+
+        let (a, b) = score(0, 5, 3, 0);
+        assert!(matches!(
+            pool_sheet.unset_score(a, b),
+            Err(PoolSheetError::FencerWithdrawn)
+        ));
+        assert!(pool_sheet.is_finished());
+    }
+
+    #[test]
+    fn exclusion_annuls_results() {
+        let mut pool_sheet = pool_with_withdrawal(WithdrawalReason::Excluded);
+        assert!(matches!(
+            pool_sheet.update_score(
+                FencerScore::new(SimpleFencer::new("Fencer1", "Fencer1"), 5, Cards::default()),
+                FencerScore::new(SimpleFencer::new("Fencer4", "Fencer4"), 3, Cards::default()),
+            ),
+            Err(PoolSheetError::FencerWithdrawn)
+        ));
+        let results = pool_sheet.finish().unwrap();
+
+        let fencer1 = results.iter().next().unwrap().1;
+        assert_eq!(*fencer1.victories(), 2);
+        assert_eq!(*fencer1.bouts(), 2);
+        assert_eq!(results.withdrawn()[0].1, WithdrawalReason::Excluded);
+
+        let json = serde_json::to_string(&pool_sheet).unwrap();
+        let pool_sheet: PoolSheet<SimpleFencer> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            pool_sheet.withdrawal(&SimpleFencer::new("Fencer4", "Fencer4")),
+            Some(WithdrawalReason::Excluded)
+        );
+    }
+
+    #[test]
+    fn weapon_applies_to_bouts() {
+        let fencers = fencers(4);
+        let mut pool_sheet = pool_with_scores(&fencers, &[]);
+        assert_eq!(pool_sheet.get_weapon(), None);
+
+        pool_sheet.set_weapon(Some(Weapon::Sabre)).unwrap();
+        assert_eq!(pool_sheet.get_weapon(), Some(Weapon::Sabre));
+        assert!(pool_sheet
+            .iter_bouts()
+            .all(|(_, bout)| bout.get_weapon() == Some(Weapon::Sabre)));
+    }
+
+    #[test]
+    fn black_card_excludes() {
+        let fencers = fencers(4);
+        let mut pool_sheet = pool_with_scores(&fencers, &[]);
+        let vs = FencerVs::new(&fencers[0], &fencers[1]).unwrap();
+        pool_sheet
+            .issue_card(&vs, &fencers[0], Offence::Group4)
+            .unwrap();
+        assert_eq!(
+            pool_sheet.withdrawal(&fencers[0]),
+            Some(WithdrawalReason::Excluded)
+        );
+    }
+
+    #[test]
+    fn event_log_survives_serialization() {
+        let fencers = fencers(4);
+        let mut pool_sheet = pool_with_scores(&fencers, &[]);
+        let vs = FencerVs::new(&fencers[0], &fencers[1]).unwrap();
+        let bout = pool_sheet.get_bout_mut(&vs).unwrap();
+        bout.record_touch(&fencers[0]).unwrap();
+        bout.record_touch(&fencers[1]).unwrap();
+        bout.issue_card(&fencers[1], Offence::Group2).unwrap();
+
+        let json = serde_json::to_string(&pool_sheet).unwrap();
+        let deserialized: PoolSheet<SimpleFencer> = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, pool_sheet);
+        assert_eq!(
+            deserialized.get_bout(&vs).unwrap().get_score(&fencers[0]),
+            Some(2)
+        );
+        assert!(!deserialized.is_finished());
+    }
+
+    #[test]
+    fn abandoned_bout_counts_as_victory() {
+        let fencers = fencers(4);
+        let mut pool_sheet = pool_with_scores(&fencers, &[]);
+        let vs = FencerVs::new(&fencers[0], &fencers[1]).unwrap();
+        let bout = pool_sheet.get_bout_mut(&vs).unwrap();
+        bout.record_touch(&fencers[0]).unwrap();
+        for _ in 0..3 {
+            bout.record_touch(&fencers[1]).unwrap();
+        }
+        bout.abandon(&fencers[1]).unwrap();
+        assert_eq!(bout.get_winner(), Some(&fencers[0]));
+
+        for (a, b) in [(0, 2), (0, 3), (1, 2), (1, 3), (2, 3)] {
+            pool_sheet
+                .update_score(
+                    FencerScore::new(fencers[a].clone(), 5, Cards::default()),
+                    FencerScore::new(fencers[b].clone(), 0, Cards::default()),
+                )
+                .unwrap();
+        }
+        assert!(pool_sheet.is_finished());
+
+        let json = serde_json::to_string(&pool_sheet).unwrap();
+        let deserialized: PoolSheet<SimpleFencer> = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, pool_sheet);
+
+        let results = deserialized.finish().unwrap();
+        let first = results.get(&fencers[0]).unwrap();
+        assert_eq!(*first.victories(), 3);
+        assert_eq!(*first.touches_scored(), 15);
+        assert_eq!(*first.touches_recieved(), 3);
+        let second = results.get(&fencers[1]).unwrap();
+        assert_eq!(*second.victories(), 2);
+        assert_eq!(*second.touches_scored(), 13);
+        assert_eq!(*second.touches_recieved(), 5);
+    }
+
     #[test]
     fn deserialize_poolsheet_intermediate() {
         let input = r#"
@@ -522,13 +1066,8 @@ mod tests {
 
     #[test]
     fn deserialize_poolsheet() {
-        let fencers = [
-            SimpleFencer::new("Fencer1"),
-            SimpleFencer::new("Fencer2"),
-            SimpleFencer::new("Fencer3"),
-            SimpleFencer::new("Fencer4"),
-        ];
-        let mut pool_sheet = PoolSheet::new(fencers.clone().into(), &SimpleBoutsCreator).unwrap();
+        let fencers = fencers(4);
+        let mut pool_sheet = pool_with_scores(&fencers, &[]);
         pool_sheet
             .update_score(
                 FencerScore::new(fencers[0].clone(), 3, Cards::default()),
@@ -541,4 +1080,60 @@ mod tests {
 
         assert_eq!(pool_sheet, new_poolsheet);
     }
+
+    #[test]
+    fn serialize_poolsheet_deterministic() {
+        let fencers = [
+            SimpleFencer::new("Fencer1", "Fencer1"),
+            SimpleFencer::new("Fencer2", "Fencer2"),
+            SimpleFencer::new("Fencer3", "Fencer3"),
+            SimpleFencer::new("Fencer4", "Fencer4"),
+        ];
+        let pool_sheet_a = PoolSheet::new(fencers.clone().into(), &SimpleBoutsCreator).unwrap();
+        let pool_sheet_b = PoolSheet::new(fencers.clone().into(), &SimpleBoutsCreator).unwrap();
+
+        let json_a = serde_json::to_string(&pool_sheet_a).unwrap();
+        let json_b = serde_json::to_string(&pool_sheet_b).unwrap();
+
+        assert_eq!(json_a, json_b);
+        assert!(json_a
+            .starts_with(r#"{"fencers":{"1":{"name":"Fencer1","id":"Fencer1","clubs":[]},"2""#));
+        assert!(json_a.contains(r#""bouts":{"[1,4]":"#));
+    }
+
+    #[test]
+    fn deserialize_legacy_pointer_keys() {
+        let cards = r#"{"yellow":0,"red":0,"group3red":0,"black":0,"passivity_yellow":0,"passivity_red":0,"passivity_black":0}"#;
+        let input = format!(
+            r#"{{
+                "fencers": {{
+                    "140300542545664": {{"name": "Fencer1", "clubs": []}},
+                    "140300542545744": {{"name": "Fencer2", "clubs": []}}
+                }},
+                "bouts": {{
+                    "[140300542545744,140300542545664]": {{
+                        "scores": [5, 3],
+                        "cards": [{cards}, {cards}],
+                        "priority": "None"
+                    }}
+                }}
+            }}"#
+        );
+
+        let pool_sheet: PoolSheet<SimpleFencer> = serde_json::from_str(&input).unwrap();
+        let fencer1 = SimpleFencer::new("Fencer1", "Fencer1");
+        let fencer2 = SimpleFencer::new("Fencer2", "Fencer2");
+        let vs: FencerVs<SimpleFencer, &SimpleFencer> = FencerVs::new(&fencer1, &fencer2).unwrap();
+        let bout = pool_sheet.get_bout(&vs).unwrap();
+        assert_eq!(bout.get_score(&fencer2), Some(5));
+        assert_eq!(bout.get_score(&fencer1), Some(3));
+
+        // Saving it again upgrades the keys to pool positions.
+        let json_out = serde_json::to_string(&pool_sheet).unwrap();
+        assert!(json_out.contains(r#""bouts":{"[2,1]":"#));
+        assert_eq!(
+            pool_sheet,
+            serde_json::from_str::<PoolSheet<SimpleFencer>>(&json_out).unwrap()
+        );
+    }
 }
@@ -8,6 +8,13 @@ pub enum PoolSheetError {
     InvalidBout,
     NoBoutFound,
     PoolNotComplete(Vec<usize>),
+    NoFencerFound,
+    FencerWithdrawn,
+    InvalidScore,
+    BoutFinished,
+    NotAllowedForWeapon,
+    NotTied,
+    NoPriority,
 }
 
 impl Display for PoolSheetError {
@@ -22,6 +29,13 @@ impl Display for PoolSheetError {
                     "the poolsheet has incomplete bouts, indexes: {indexes:?}"
                 )
             }
+            NoFencerFound => write!(f, "this fencer is not in this poolsheet"),
+            FencerWithdrawn => write!(f, "the fencer has withdrawn from this poolsheet"),
+            NotTied => write!(f, "priority can only be drawn when the scores are tied"),
+            NoPriority => write!(f, "a tied bout cannot end without priority"),
+            NotAllowedForWeapon => write!(f, "this is not allowed in this weapon"),
+            BoutFinished => write!(f, "the bout is already over"),
+            InvalidScore => write!(f, "the scores are not possible for this bout format"),
             UnsupportedParticipantCount => write!(
                 f,
                 "a poolsheet cannot be generated with the given amount of fencers"
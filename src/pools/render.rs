@@ -0,0 +1,423 @@
+//! Printable pool sheets in the usual USA Fencing layout.
+
+use std::fmt::{Debug, Write};
+
+use crate::{fencer::Fencer, formats::escape};
+
+use super::{PoolResults, PoolSheet};
+
+/// One cell of the grid, read along the row's fencer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridCell {
+    /// A fencer against themselves.
+    Blank,
+    NotFenced,
+    Victory(u8),
+    Defeat(u8),
+    /// Will not be fenced because a fencer withdrew.
+    Abandoned,
+}
+
+impl GridCell {
+    /// How the cell is written on a paper sheet, eg. "V5" or "D3".
+    pub fn label(&self) -> String {
+        match self {
+            GridCell::Blank | GridCell::NotFenced => String::new(),
+            GridCell::Victory(touches) => format!("V{touches}"),
+            GridCell::Defeat(touches) => format!("D{touches}"),
+            GridCell::Abandoned => "-".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct GridTotals {
+    victories: u8,
+    touches_scored: u8,
+    touches_recieved: u8,
+    indicator: i16,
+    place: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct GridRow {
+    name: String,
+    cells: Vec<GridCell>,
+    totals: Option<GridTotals>,
+}
+
+/// A pool sheet laid out for printing, rendered with `to_text`, `to_html` or `to_svg`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolSheetGrid {
+    rows: Vec<GridRow>,
+    /// 1 based positions of each bout in the order they are fenced.
+    bouts: Vec<(usize, usize)>,
+}
+
+const TOTALS_HEADERS: [&str; 5] = ["V", "TS", "TR", "Ind", "Pl"];
+
+impl PoolSheetGrid {
+    /// Lays out a pool sheet, the totals are filled in once the pool is finished.
+    pub fn new<T: Fencer + Debug>(poolsheet: &PoolSheet<T>) -> PoolSheetGrid {
+        PoolSheetGrid::layout(poolsheet, poolsheet.finish().ok().as_ref())
+    }
+
+    /// Lays out a pool sheet with the totals from already computed results.
+    pub fn with_results<T: Fencer + Debug>(
+        poolsheet: &PoolSheet<T>,
+        results: &PoolResults<T>,
+    ) -> PoolSheetGrid {
+        PoolSheetGrid::layout(poolsheet, Some(results))
+    }
+
+    fn layout<T: Fencer + Debug>(
+        poolsheet: &PoolSheet<T>,
+        results: Option<&PoolResults<T>>,
+    ) -> PoolSheetGrid {
+        let fencers = poolsheet.get_fencers();
+        let position = |fencer: &T| {
+            fencers
+                .iter()
+                .position(|pool_fencer| pool_fencer.get_id() == fencer.get_id())
+                .expect("Bouts are between poolsheet fencers")
+        };
+
+        let mut rows: Vec<GridRow> = fencers
+            .iter()
+            .enumerate()
+            .map(|(index, fencer)| GridRow {
+                name: fencer.get_fullname(),
+                cells: (0..fencers.len())
+                    .map(|other| {
+                        if other == index {
+                            GridCell::Blank
+                        } else {
+                            GridCell::NotFenced
+                        }
+                    })
+                    .collect(),
+                totals: results
+                    .and_then(|results| results.get(fencer))
+                    .map(|result| GridTotals {
+                        victories: *result.victories(),
+                        touches_scored: *result.touches_scored(),
+                        touches_recieved: *result.touches_recieved(),
+                        indicator: *result.indicator(),
+                        place: result.place().to_string(),
+                    }),
+            })
+            .collect();
+
+        let mut bouts = Vec::with_capacity(poolsheet.bouts.len());
+        for (_, bout) in poolsheet.iter_bouts() {
+            let (fencer_a, fencer_b) = bout.get_fencers();
+            let (a, b) = (position(fencer_a), position(fencer_b));
+            bouts.push((a + 1, b + 1));
+
+            let (touches_a, touches_b) = bout.touches();
+            let (cell_a, cell_b) = match bout.get_winner() {
+                Some(winner) if winner.get_id() == fencer_a.get_id() => {
+                    (GridCell::Victory(touches_a), GridCell::Defeat(touches_b))
+                }
+                Some(_) => (GridCell::Defeat(touches_a), GridCell::Victory(touches_b)),
+                None if poolsheet.is_abandoned(bout) => (GridCell::Abandoned, GridCell::Abandoned),
+                None => continue,
+            };
+            rows[a].cells[b] = cell_a;
+            rows[b].cells[a] = cell_b;
+        }
+
+        PoolSheetGrid { rows, bouts }
+    }
+
+    pub fn get_cell(&self, row: usize, column: usize) -> Option<GridCell> {
+        self.rows.get(row)?.cells.get(column).copied()
+    }
+
+    fn headers(&self) -> Vec<String> {
+        (1..=self.rows.len())
+            .map(|position| position.to_string())
+            .chain(TOTALS_HEADERS.iter().map(|header| header.to_string()))
+            .collect()
+    }
+
+    /// Cells after the name, the grid then the totals.
+    fn row_labels(&self, row: &GridRow) -> Vec<String> {
+        let totals = match &row.totals {
+            Some(totals) => vec![
+                totals.victories.to_string(),
+                totals.touches_scored.to_string(),
+                totals.touches_recieved.to_string(),
+                totals.indicator.to_string(),
+                totals.place.clone(),
+            ],
+            None => vec![String::new(); TOTALS_HEADERS.len()],
+        };
+        row.cells
+            .iter()
+            .map(GridCell::label)
+            .chain(totals)
+            .collect()
+    }
+
+    fn bout_lines(&self) -> Vec<String> {
+        self.bouts
+            .iter()
+            .enumerate()
+            .map(|(index, (a, b))| {
+                format!(
+                    "{}. {a}-{b} {} vs {}",
+                    index + 1,
+                    self.rows[a - 1].name,
+                    self.rows[b - 1].name
+                )
+            })
+            .collect()
+    }
+
+    pub fn to_text(&self) -> String {
+        let headers = self.headers();
+        let labels: Vec<Vec<String>> = self.rows.iter().map(|row| self.row_labels(row)).collect();
+        let name_width = self
+            .rows
+            .iter()
+            .map(|row| row.name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("Name".len());
+        let widths: Vec<usize> = headers
+            .iter()
+            .enumerate()
+            .map(|(column, header)| {
+                labels
+                    .iter()
+                    .map(|row| row[column].len())
+                    .max()
+                    .unwrap_or(0)
+                    .max(header.len())
+                    .max(2)
+            })
+            .collect();
+
+        let line = |position: &str, name: &str, cells: &[String]| {
+            let mut line = format!("{position:>2} {name:<name_width$}");
+            for (cell, width) in cells.iter().zip(&widths) {
+                let _ = write!(line, " | {cell:^width$}");
+            }
+            line.push_str(" |");
+            line
+        };
+
+        let mut text = line("#", "Name", &headers);
+        text.push('\n');
+        let separator_width = text.chars().count() - 1;
+        text.push_str(&"-".repeat(separator_width));
+        text.push('\n');
+        for (index, (row, labels)) in self.rows.iter().zip(&labels).enumerate() {
+            text.push_str(&line(&(index + 1).to_string(), &row.name, labels));
+            text.push('\n');
+        }
+
+        text.push_str("\nBout Order\n");
+        for bout in self.bout_lines() {
+            text.push_str(&bout);
+            text.push('\n');
+        }
+        text
+    }
+
+    /// A standalone HTML page.
+    pub fn to_html(&self) -> String {
+        let mut html = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Pool Sheet</title>\n\
+             <style>\n\
+             table.pool { border-collapse: collapse; font-family: sans-serif; }\n\
+             table.pool th, table.pool td { border: 1px solid #000; min-width: 2em; height: 1.8em; text-align: center; }\n\
+             table.pool td.name { text-align: left; padding: 0 0.5em; }\n\
+             table.pool td.blank { background: #000; }\n\
+             </style>\n</head>\n<body>\n<table class=\"pool\">\n<tr><th>#</th><th>Name</th>",
+        );
+        for header in self.headers() {
+            let _ = write!(html, "<th>{header}</th>");
+        }
+        html.push_str("</tr>\n");
+
+        for (index, row) in self.rows.iter().enumerate() {
+            let _ = write!(
+                html,
+                "<tr><td>{}</td><td class=\"name\">{}</td>",
+                index + 1,
+                escape(&row.name)
+            );
+            for (column, label) in self.row_labels(row).iter().enumerate() {
+                if row.cells.get(column) == Some(&GridCell::Blank) {
+                    html.push_str("<td class=\"blank\"></td>");
+                } else {
+                    let _ = write!(html, "<td>{}</td>", escape(label));
+                }
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n<h2>Bout Order</h2>\n<ol class=\"bouts\">\n");
+        for (a, b) in &self.bouts {
+            let _ = writeln!(
+                html,
+                "<li>{a}-{b} {} vs {}</li>",
+                escape(&self.rows[a - 1].name),
+                escape(&self.rows[b - 1].name)
+            );
+        }
+        html.push_str("</ol>\n</body>\n</html>\n");
+        html
+    }
+
+    pub fn to_svg(&self) -> String {
+        const CELL_WIDTH: usize = 40;
+        const CELL_HEIGHT: usize = 24;
+        const NAME_WIDTH: usize = 180;
+        const LINE_HEIGHT: usize = 18;
+
+        let headers = self.headers();
+        let grid_width = CELL_WIDTH + NAME_WIDTH + headers.len() * CELL_WIDTH;
+        let grid_height = (self.rows.len() + 1) * CELL_HEIGHT;
+        let bouts_top = grid_height + 2 * LINE_HEIGHT;
+        let height = bouts_top + (self.bouts.len() + 1) * LINE_HEIGHT;
+        let column_x = |column: usize| CELL_WIDTH + NAME_WIDTH + column * CELL_WIDTH;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{grid_width}\" height=\"{height}\" \
+             font-family=\"sans-serif\" font-size=\"14\">\n"
+        );
+        let mut cell = |x: usize, y: usize, width: usize, text: &str, fill: &str, anchor: &str| {
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{x}\" y=\"{y}\" width=\"{width}\" height=\"{CELL_HEIGHT}\" fill=\"{fill}\" stroke=\"#000\"/>"
+            );
+            if !text.is_empty() {
+                let (text_x, text_anchor) = match anchor {
+                    "start" => (x + 6, "start"),
+                    _ => (x + width / 2, "middle"),
+                };
+                let _ = writeln!(
+                    svg,
+                    "<text x=\"{text_x}\" y=\"{}\" text-anchor=\"{text_anchor}\">{}</text>",
+                    y + CELL_HEIGHT - 7,
+                    escape(text)
+                );
+            }
+        };
+
+        cell(0, 0, CELL_WIDTH, "#", "#fff", "middle");
+        cell(CELL_WIDTH, 0, NAME_WIDTH, "Name", "#fff", "start");
+        for (column, header) in headers.iter().enumerate() {
+            cell(column_x(column), 0, CELL_WIDTH, header, "#fff", "middle");
+        }
+        for (index, row) in self.rows.iter().enumerate() {
+            let y = (index + 1) * CELL_HEIGHT;
+            cell(0, y, CELL_WIDTH, &(index + 1).to_string(), "#fff", "middle");
+            cell(CELL_WIDTH, y, NAME_WIDTH, &row.name, "#fff", "start");
+            for (column, label) in self.row_labels(row).iter().enumerate() {
+                let fill = match row.cells.get(column) {
+                    Some(GridCell::Blank) => "#000",
+                    _ => "#fff",
+                };
+                cell(column_x(column), y, CELL_WIDTH, label, fill, "middle");
+            }
+        }
+
+        let _ = writeln!(
+            svg,
+            "<text x=\"0\" y=\"{}\" font-weight=\"bold\">Bout Order</text>",
+            bouts_top
+        );
+        for (index, bout) in self.bout_lines().iter().enumerate() {
+            let _ = writeln!(
+                svg,
+                "<text x=\"0\" y=\"{}\">{}</text>",
+                bouts_top + (index + 1) * LINE_HEIGHT,
+                escape(bout)
+            );
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bout::FencerVs,
+        fencer::SimpleFencer,
+        pools::{
+            bout_creation::SimpleBoutsCreator,
+            test_util::{every_bout, pool_with_scores},
+            PoolSheet,
+        },
+    };
+
+    use super::{GridCell, PoolSheetGrid};
+
+    fn fencers() -> [SimpleFencer; 4] {
+        [
+            SimpleFencer::new("Alice", "Alice"),
+            SimpleFencer::new("Bob", "Bob"),
+            SimpleFencer::new("Carol & Co", "Carol & Co"),
+            SimpleFencer::new("Dave", "Dave"),
+        ]
+    }
+
+    #[test]
+    fn empty_sheet_renders() {
+        let poolsheet = PoolSheet::new(fencers().into(), &SimpleBoutsCreator).unwrap();
+        let grid = PoolSheetGrid::new(&poolsheet);
+        assert_eq!(grid.get_cell(0, 0), Some(GridCell::Blank));
+        assert_eq!(grid.get_cell(0, 3), Some(GridCell::NotFenced));
+
+        let text = grid.to_text();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines[0],
+            " # Name       | 1  | 2  | 3  | 4  | V  | TS | TR | Ind | Pl |"
+        );
+        assert_eq!(
+            lines[2],
+            " 1 Alice      |    |    |    |    |    |    |    |     |    |"
+        );
+        assert!(text.contains("1. 1-4 Alice vs Dave"));
+
+        let html = grid.to_html();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("Carol &amp; Co"));
+        assert_eq!(html.matches("<li>").count(), 6);
+
+        let svg = grid.to_svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert!(svg.contains("Carol &amp; Co"));
+    }
+
+    #[test]
+    fn finished_sheet_has_totals() {
+        let fencers = fencers();
+        let mut poolsheet =
+            pool_with_scores(&fencers, &every_bout(4, |a, b| (5, (a + b) as u8 % 5)));
+
+        let grid = PoolSheetGrid::new(&poolsheet);
+        assert_eq!(grid.get_cell(0, 1), Some(GridCell::Victory(5)));
+        assert_eq!(grid.get_cell(1, 0), Some(GridCell::Defeat(1)));
+        let text = grid.to_text();
+        assert!(text.contains(" 1 Alice      |    | V5 | V5 | V5 | 3  | 15 | 6  |  9  | 1  |"));
+
+        let results = poolsheet.finish().unwrap();
+        assert_eq!(PoolSheetGrid::with_results(&poolsheet, &results), grid);
+
+        // Unfinished again, the totals are left empty.
+        let vs = FencerVs::new(fencers[2].clone(), fencers[3].clone()).unwrap();
+        poolsheet.get_bout_mut(&vs).unwrap().unset_scores();
+        let grid = PoolSheetGrid::new(&poolsheet);
+        assert_eq!(grid.get_cell(2, 3), Some(GridCell::NotFenced));
+        assert!(grid
+            .to_text()
+            .contains(" 1 Alice      |    | V5 | V5 | V5 |    |"));
+    }
+}
@@ -1,19 +1,19 @@
 use derive_getters::Getters;
-use indexmap::{map::Iter, IndexMap};
+use indexmap::IndexMap;
 
 use crate::fencer::Fencer;
 use std::fmt::Debug;
-use std::{borrow::Borrow, cmp::Ordering, rc::Rc};
+use std::{cmp::Ordering, rc::Rc};
 
-use super::Placement;
+use super::tie_break::{HeadToHead, TieBreaker, TieResolution};
 use super::PoolSheet;
-
-use rand::random;
+use super::{Placement, WithdrawalReason};
 
 #[derive(Debug, Clone, Getters)]
 pub struct FencerResult<T: Fencer> {
     fencer: Rc<T>,
     victories: u8,
+    bouts: u8,
     touches_scored: u8,
     touches_recieved: u8,
     indicator: i16,
@@ -25,6 +25,7 @@ impl<T: Fencer> FencerResult<T> {
         FencerResult {
             fencer: fencer.clone(),
             victories: 0,
+            bouts: 0,
             touches_scored: 0,
             touches_recieved: 0,
             indicator: 0,
@@ -36,21 +37,17 @@ impl<T: Fencer> FencerResult<T> {
         self.indicator = i16::from(self.touches_scored) - i16::from(self.touches_recieved);
     }
 
-    /// Compares result, choosing one at random if they are equal.
-    fn rnd_cmp(&self, other: &Self) -> Ordering {
-        self.cmp(other).then_with(|| {
-            if random::<bool>() {
-                Ordering::Greater
-            } else {
-                Ordering::Less
-            }
-        })
+    // Fencers can have fenced a different number of bouts after a medical withdrawal.
+    fn cmp_victory_ratio(&self, other: &Self) -> Ordering {
+        let ratio = u16::from(self.victories) * u16::from(other.bouts);
+        let other_ratio = u16::from(other.victories) * u16::from(self.bouts);
+        ratio.cmp(&other_ratio)
     }
 }
 
 impl<T: Fencer> PartialEq for FencerResult<T> {
     fn eq(&self, other: &Self) -> bool {
-        (self.victories == other.victories) & (self.indicator == other.indicator)
+        self.cmp(other) == Ordering::Equal
     }
 }
 
@@ -65,53 +62,69 @@ impl<T: Fencer> PartialOrd for FencerResult<T> {
 
 impl<T: Fencer> Ord for FencerResult<T> {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.victories
-            .cmp(&other.victories)
+        self.cmp_victory_ratio(other)
             .then_with(|| self.indicator.cmp(&other.indicator))
     }
 }
 
 #[derive(Debug, Clone)]
-pub struct PoolResults<T: Fencer>(IndexMap<Rc<T>, FencerResult<T>>);
+pub struct PoolResults<T: Fencer> {
+    results: IndexMap<T::Id, FencerResult<T>>,
+    tie_resolutions: Vec<TieResolution>,
+    withdrawn: Vec<(Rc<T>, WithdrawalReason)>,
+}
 
 impl<T: Fencer + Debug> PoolResults<T> {
+    /// Two way ties go to head to head, larger ties to touches scored.
     pub fn new(poolsheet: &PoolSheet<T>) -> PoolResults<T> {
+        PoolResults::with_tie_breaker(poolsheet, &mut HeadToHead)
+    }
+
+    /// Uses `tie_breaker` for fencers tied on V/M and indicator.
+    pub fn with_tie_breaker<B: TieBreaker<T>>(
+        poolsheet: &PoolSheet<T>,
+        tie_breaker: &mut B,
+    ) -> PoolResults<T> {
         let mut results_map = IndexMap::new();
+        let mut withdrawn = Vec::new();
         for fencer in poolsheet.fencers.iter() {
-            results_map.insert(fencer.clone(), FencerResult::new_zeroed(fencer.clone()));
+            match poolsheet.withdrawal(fencer) {
+                Some(reason) => withdrawn.push((fencer.clone(), reason)),
+                None => {
+                    results_map.insert(fencer.get_id(), FencerResult::new_zeroed(fencer.clone()));
+                }
+            }
         }
 
         for (_, bout) in poolsheet.bouts.iter() {
+            if !poolsheet.counts(bout) {
+                continue;
+            }
             let (fencer_a, fencer_b) = (&bout.fencers.0, &bout.fencers.1);
-            let (score_a, score_b) = bout
-                .get_scores()
-                .expect("Bout Should have been checked for completion");
+            // Bouts decided by the committee keep the touches scored before they were stopped,
+            // except that the winner of an abandoned bout is given the target.
+            let (score_a, score_b) = bout.touches();
 
             let bout_winner = bout
                 .get_winner()
                 .expect("Winner should be set before a call to make results is done");
 
-            {
-                let fencer_a_result = results_map
-                    .get_mut(fencer_a)
-                    .expect("The map should be populated with all possible fencers");
-
+            // Fencers who withdrew for medical reasons keep their finished bouts but are not ranked.
+            if let Some(fencer_a_result) = results_map.get_mut(&fencer_a.get_id()) {
+                fencer_a_result.bouts += 1;
                 fencer_a_result.touches_scored += score_a;
                 fencer_a_result.touches_recieved += score_b;
 
-                if <Rc<T> as Borrow<T>>::borrow(fencer_a) == bout_winner {
+                if fencer_a.get_id() == bout_winner.get_id() {
                     fencer_a_result.victories += 1
                 }
             }
-            {
-                let fencer_b_result = results_map
-                    .get_mut(fencer_b)
-                    .expect("The map should be populated with all possible fencers");
-
+            if let Some(fencer_b_result) = results_map.get_mut(&fencer_b.get_id()) {
+                fencer_b_result.bouts += 1;
                 fencer_b_result.touches_scored += score_b;
                 fencer_b_result.touches_recieved += score_a;
 
-                if <Rc<T> as Borrow<T>>::borrow(fencer_b) == bout_winner {
+                if fencer_b.get_id() == bout_winner.get_id() {
                     fencer_b_result.victories += 1
                 }
             }
@@ -121,39 +134,87 @@ impl<T: Fencer + Debug> PoolResults<T> {
             fencer_result.calculate_indicator();
         }
 
-        results_map.sort_by(|_, a, _, b| b.rnd_cmp(a));
+        // Stable sort, so tied fencers stay in pool order until the tie is broken.
+        results_map.sort_by(|_, a, _, b| b.cmp(a));
+
+        let position = |id: &T::Id| {
+            poolsheet
+                .fencers
+                .iter()
+                .position(|pool_fencer| pool_fencer.get_id() == *id)
+                .expect("Results are made from the poolsheet fencers")
+                + 1
+        };
+
+        let mut ordered_results = IndexMap::with_capacity(results_map.len());
+        let mut tie_resolutions = Vec::new();
+        let mut place = 1;
+        let mut remaining = results_map.into_iter().peekable();
+        while let Some(first) = remaining.next() {
+            let mut group = vec![first];
+            while let Some(next) = remaining.next_if(|(_, result)| *result == group[0].1) {
+                group.push(next);
+            }
 
-        let first_result = results_map
-            .get_index_mut(0)
-            .expect("Should have at least one fencer")
-            .1;
-        first_result.place = Placement::Absolute(1);
+            if group.len() == 1 {
+                let (id, mut result) = group.pop().expect("Group has one result");
+                result.place = Placement::Absolute(place);
+                ordered_results.insert(id, result);
+                place += 1;
+                continue;
+            }
 
-        let mut results_iter = results_map.iter_mut();
+            let mut group: IndexMap<usize, (T::Id, FencerResult<T>)> = group
+                .into_iter()
+                .map(|(id, result)| (position(&id), (id, result)))
+                .collect();
+            let tied: Vec<usize> = group.keys().copied().collect();
 
-        let (_, mut last_result) = results_iter
-            .next()
-            .expect("Should have at least one fencer");
-        for (_, result) in results_iter {
-            match last_result.cmp(&result) {
-                Ordering::Greater => {
-                    result.place = Placement::Absolute(last_result.place.inner() + 1)
-                }
-                Ordering::Equal => {
-                    last_result.place.to_tied();
-                    result.place = Placement::Tied(last_result.place.inner())
-                }
-                Ordering::Less => {
-                    panic!("The results should have been sorted!")
+            let mut resolution = tie_breaker.break_tie(&tied, poolsheet);
+            if !resolution.resolves(&tied) {
+                resolution = TieResolution::left_tied(&tied);
+            }
+
+            for sub_group in resolution.order() {
+                for position in sub_group {
+                    let (id, mut result) = group
+                        .swap_remove(position)
+                        .expect("Resolution was checked to contain the tied fencers");
+                    result.place = if sub_group.len() == 1 {
+                        Placement::Absolute(place)
+                    } else {
+                        Placement::Tied(place)
+                    };
+                    ordered_results.insert(id, result);
                 }
+                place += sub_group.len();
             }
-            last_result = result;
+            tie_resolutions.push(resolution);
+        }
+
+        PoolResults {
+            results: ordered_results,
+            tie_resolutions,
+            withdrawn,
         }
+    }
+
+    /// Fencers and their results in placement order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Rc<T>, &FencerResult<T>)> {
+        self.results.values().map(|result| (&result.fencer, result))
+    }
+
+    pub fn get(&self, fencer: &T) -> Option<&FencerResult<T>> {
+        self.results.get(&fencer.get_id())
+    }
 
-        PoolResults(results_map)
+    /// Fencers who withdrew or were excluded, these are not part of the ranking.
+    pub fn withdrawn(&self) -> &[(Rc<T>, WithdrawalReason)] {
+        &self.withdrawn
     }
 
-    pub fn iter(&self) -> Iter<Rc<T>, FencerResult<T>> {
-        self.0.iter()
+    /// Store these with the poolsheet to get the same results again.
+    pub fn tie_resolutions(&self) -> &[TieResolution] {
+        &self.tie_resolutions
     }
 }
@@ -0,0 +1,280 @@
+use std::fmt::Debug;
+
+use indexmap::IndexSet;
+
+use crate::fencer::{Affiliated, Fencer};
+
+use super::bout_creation::BoutsCreator;
+use super::{PoolSheet, PoolSheetError, RoundResults};
+
+pub const DEFAULT_MAX_POOL_SIZE: usize = 7;
+
+/// Pools as even as possible with the larger pools first.
+pub fn pool_sizes(fencer_count: usize, max_pool_size: usize) -> Result<Vec<usize>, PoolSheetError> {
+    if fencer_count == 0 || max_pool_size == 0 {
+        return Err(PoolSheetError::UnsupportedParticipantCount);
+    }
+    let pool_count = fencer_count.div_ceil(max_pool_size);
+    let base_size = fencer_count / pool_count;
+    let larger_pools = fencer_count % pool_count;
+    Ok((0..pool_count)
+        .map(|pool| {
+            if pool < larger_pools {
+                base_size + 1
+            } else {
+                base_size
+            }
+        })
+        .collect())
+}
+
+/// Which pool each seed goes into.
+fn serpentine(sizes: &[usize]) -> Vec<Vec<usize>> {
+    let mut pools: Vec<Vec<usize>> = sizes.iter().map(|size| Vec::with_capacity(*size)).collect();
+    let fencer_count: usize = sizes.iter().sum();
+    let mut seed = 0;
+    let mut row = 0;
+    while seed < fencer_count {
+        let mut order: Vec<usize> = (0..sizes.len()).collect();
+        if row % 2 == 1 {
+            order.reverse();
+        }
+        for pool in order {
+            if seed < fencer_count && pools[pool].len() < sizes[pool] {
+                pools[pool].push(seed);
+                seed += 1;
+            }
+        }
+        row += 1;
+    }
+    pools
+}
+
+/// Club conflicts followed by division conflicts in a pool.
+fn conflicts<T: Affiliated>(entries: &[T], pool: &[usize]) -> (usize, usize) {
+    let mut conflicts = (0, 0);
+    for (index, a) in pool.iter().enumerate() {
+        for b in pool[index + 1..].iter() {
+            if entries[*a].same_club(&entries[*b]) {
+                conflicts.0 += 1;
+            } else if entries[*a].same_division(&entries[*b]) {
+                conflicts.1 += 1;
+            }
+        }
+    }
+    conflicts
+}
+
+fn add(a: (usize, usize), b: (usize, usize)) -> (usize, usize) {
+    (a.0 + b.0, a.1 + b.1)
+}
+
+/// Swaps fencers within a row while it lowers the conflicts.
+fn separate<T: Affiliated>(entries: &[T], pools: &mut [Vec<usize>]) {
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for a in 0..pools.len() {
+            for b in (a + 1)..pools.len() {
+                let rows = pools[a].len().min(pools[b].len());
+                // Start from the bottom so lower seeds get moved before higher ones.
+                for row in (0..rows).rev() {
+                    let before = add(conflicts(entries, &pools[a]), conflicts(entries, &pools[b]));
+
+                    let mut swapped_a = pools[a].clone();
+                    let mut swapped_b = pools[b].clone();
+                    std::mem::swap(&mut swapped_a[row], &mut swapped_b[row]);
+                    let after = add(
+                        conflicts(entries, &swapped_a),
+                        conflicts(entries, &swapped_b),
+                    );
+
+                    if after < before {
+                        pools[a] = swapped_a;
+                        pools[b] = swapped_b;
+                        improved = true;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Fencers are placed by seed, then swapped to split up clubs and divisions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolRound<T: Fencer> {
+    pools: Vec<PoolSheet<T>>,
+}
+
+impl<T: Fencer + Affiliated + Debug> PoolRound<T> {
+    /// Creates pools of at most 7 fencers from entries in seed order.
+    pub fn new<C>(entries: IndexSet<T>, creator: &C) -> Result<PoolRound<T>, PoolSheetError>
+    where
+        C: BoutsCreator<T>,
+    {
+        PoolRound::with_max_pool_size(entries, creator, DEFAULT_MAX_POOL_SIZE)
+    }
+
+    pub fn with_max_pool_size<C>(
+        entries: IndexSet<T>,
+        creator: &C,
+        max_pool_size: usize,
+    ) -> Result<PoolRound<T>, PoolSheetError>
+    where
+        C: BoutsCreator<T>,
+    {
+        let entries: Vec<T> = entries.into_iter().collect();
+        let sizes = pool_sizes(entries.len(), max_pool_size)?;
+        let mut assignments = serpentine(&sizes);
+        separate(&entries, &mut assignments);
+
+        let mut pools = Vec::with_capacity(assignments.len());
+        for mut assignment in assignments {
+            assignment.sort();
+            let fencers = assignment
+                .into_iter()
+                .map(|seed| entries[seed].clone())
+                .collect();
+            pools.push(PoolSheet::new(fencers, creator)?);
+        }
+
+        Ok(PoolRound { pools })
+    }
+}
+
+impl<T: Fencer + Debug> PoolRound<T> {
+    /// A round made from pools that were already drawn, eg. imported from another program.
+    pub fn from_pools(pools: Vec<PoolSheet<T>>) -> PoolRound<T> {
+        PoolRound { pools }
+    }
+
+    pub fn get_pools(&self) -> &[PoolSheet<T>] {
+        &self.pools
+    }
+
+    pub fn get_pool(&self, index: usize) -> Option<&PoolSheet<T>> {
+        self.pools.get(index)
+    }
+
+    pub fn get_pool_mut(&mut self, index: usize) -> Option<&mut PoolSheet<T>> {
+        self.pools.get_mut(index)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.pools.iter().all(|pool| pool.is_finished())
+    }
+
+    /// Ranks every fencer across all the pools, errors if any pool is not complete.
+    pub fn finish(&self) -> Result<RoundResults<T>, PoolSheetError> {
+        let results = self
+            .pools
+            .iter()
+            .map(|pool| pool.finish())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(RoundResults::new(&results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexSet;
+
+    use super::{pool_sizes, serpentine, PoolRound};
+    use crate::{
+        fencer::{Fencer, SimpleFencer},
+        organizations::usafencing::{club::Club, fencer::USAFFencer, Division},
+        pools::bout_creation::SimpleBoutsCreator,
+    };
+
+    fn names(round: &PoolRound<SimpleFencer>) -> Vec<Vec<String>> {
+        round
+            .get_pools()
+            .iter()
+            .map(|pool| {
+                pool.get_fencers()
+                    .iter()
+                    .map(|fencer| fencer.get_fullname())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pool_sizes_test() {
+        assert_eq!(pool_sizes(20, 7).unwrap(), vec![7, 7, 6]);
+        assert_eq!(pool_sizes(14, 7).unwrap(), vec![7, 7]);
+        assert_eq!(pool_sizes(13, 7).unwrap(), vec![7, 6]);
+        assert_eq!(pool_sizes(6, 7).unwrap(), vec![6]);
+        assert!(pool_sizes(0, 7).is_err());
+    }
+
+    #[test]
+    fn serpentine_test() {
+        assert_eq!(
+            serpentine(&[4, 4, 3]),
+            vec![vec![0, 5, 6, 10], vec![1, 4, 7, 9], vec![2, 3, 8]]
+        );
+    }
+
+    #[test]
+    fn snake_without_conflicts() {
+        let fencers: Vec<SimpleFencer> = (1..=10).map(|i| SimpleFencer::new(i, i)).collect();
+        let round = PoolRound::new(IndexSet::from_iter(fencers), &SimpleBoutsCreator).unwrap();
+
+        assert_eq!(
+            names(&round),
+            vec![
+                vec!["1", "4", "5", "8", "9"],
+                vec!["2", "3", "6", "7", "10"]
+            ]
+        );
+    }
+
+    #[test]
+    fn teammates_separated() {
+        let mut fencers: Vec<SimpleFencer> = (1..=10).map(|i| SimpleFencer::new(i, i)).collect();
+        fencers[0].add_club("Alpha Fencing Club", "AFC");
+        fencers[3].add_club("Alpha Fencing Club", "AFC");
+
+        let round = PoolRound::new(IndexSet::from_iter(fencers), &SimpleBoutsCreator).unwrap();
+
+        assert_eq!(
+            names(&round),
+            vec![
+                vec!["1", "3", "5", "8", "9"],
+                vec!["2", "4", "6", "7", "10"]
+            ]
+        );
+    }
+
+    #[test]
+    fn divisions_separated() {
+        let mut fencers: Vec<USAFFencer> = (1..=8)
+            .map(|i| USAFFencer::with_name(i, format!("{i}")))
+            .collect();
+        fencers[0].add_club(Club::new(
+            "Alpha Fencing Club".to_string(),
+            "AFC".to_string(),
+            1,
+            Division::Alabama,
+        ));
+        fencers[3].add_club(Club::new(
+            "Beta Fencing Club".to_string(),
+            "BFC".to_string(),
+            2,
+            Division::Alabama,
+        ));
+
+        let round = PoolRound::with_max_pool_size(
+            IndexSet::from_iter(fencers.clone()),
+            &SimpleBoutsCreator,
+            4,
+        )
+        .unwrap();
+        let expected: [&[usize]; 2] = [&[0, 2, 4, 7], &[1, 3, 5, 6]];
+        for (pool, expected) in round.get_pools().iter().zip(expected) {
+            let expected: Vec<&USAFFencer> = expected.iter().map(|i| &fencers[*i]).collect();
+            assert_eq!(pool.get_fencers(), expected);
+        }
+    }
+}
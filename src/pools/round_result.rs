@@ -0,0 +1,237 @@
+use derive_getters::Getters;
+use indexmap::IndexMap;
+
+use crate::fencer::Fencer;
+use std::fmt::Debug;
+use std::{cmp::Ordering, rc::Rc};
+
+use super::result::FencerResult;
+use super::{Placement, PoolResults};
+
+/// A fencer's results over the whole round of pools.
+#[derive(Debug, Clone, Getters)]
+pub struct RoundFencerResult<T: Fencer> {
+    fencer: Rc<T>,
+    victories: u8,
+    bouts: u8,
+    touches_scored: u8,
+    touches_recieved: u8,
+    indicator: i16,
+    place: Placement,
+}
+
+impl<T: Fencer> RoundFencerResult<T> {
+    fn from_pool_result(result: &FencerResult<T>) -> RoundFencerResult<T> {
+        RoundFencerResult {
+            fencer: result.fencer().clone(),
+            victories: *result.victories(),
+            bouts: *result.bouts(),
+            touches_scored: *result.touches_scored(),
+            touches_recieved: *result.touches_recieved(),
+            indicator: *result.indicator(),
+            place: Placement::Absolute(0),
+        }
+    }
+
+    /// Victories divided by bouts fenced (V/M), 0 if no bouts were fenced.
+    pub fn victory_ratio(&self) -> f64 {
+        if self.bouts == 0 {
+            0.0
+        } else {
+            f64::from(self.victories) / f64::from(self.bouts)
+        }
+    }
+
+    fn cmp_victory_ratio(&self, other: &Self) -> Ordering {
+        // Cross multiply so the ratios can be compared without floats.
+        let ratio = u16::from(self.victories) * u16::from(other.bouts);
+        let other_ratio = u16::from(other.victories) * u16::from(self.bouts);
+        ratio.cmp(&other_ratio)
+    }
+}
+
+impl<T: Fencer> PartialEq for RoundFencerResult<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Fencer> Eq for RoundFencerResult<T> {}
+
+impl<T: Fencer> PartialOrd for RoundFencerResult<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Fencer> Ord for RoundFencerResult<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_victory_ratio(other)
+            .then_with(|| self.indicator.cmp(&other.indicator))
+            .then_with(|| self.touches_scored.cmp(&other.touches_scored))
+    }
+}
+
+/// Ranked by V/M, then indicator, then touches scored.
+#[derive(Debug, Clone)]
+pub struct RoundResults<T: Fencer>(IndexMap<T::Id, RoundFencerResult<T>>);
+
+impl<T: Fencer + Debug> RoundResults<T> {
+    pub fn new(pool_results: &[PoolResults<T>]) -> RoundResults<T> {
+        let mut results_map = IndexMap::new();
+        for pool in pool_results {
+            for (fencer, result) in pool.iter() {
+                results_map.insert(fencer.get_id(), RoundFencerResult::from_pool_result(result));
+            }
+        }
+
+        results_map.sort_by(|_, a, _, b| b.cmp(a));
+
+        let mut last_result: Option<&RoundFencerResult<T>> = None;
+        let mut places = Vec::with_capacity(results_map.len());
+        for (index, (_, result)) in results_map.iter().enumerate() {
+            let place = match last_result {
+                Some(last) if last.cmp(result) == Ordering::Equal => {
+                    Placement::Tied(places.last().map(Placement::inner).unwrap_or(1))
+                }
+                _ => Placement::Absolute(index + 1),
+            };
+            places.push(place);
+            last_result = Some(result);
+        }
+
+        // Mark the first fencer of a tie as tied as well.
+        for index in 1..places.len() {
+            if let Placement::Tied(place) = places[index] {
+                places[index - 1] = Placement::Tied(place);
+            }
+        }
+
+        for ((_, result), place) in results_map.iter_mut().zip(places) {
+            result.place = place;
+        }
+
+        RoundResults(results_map)
+    }
+
+    /// Fencers and their results in placement order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Rc<T>, &RoundFencerResult<T>)> {
+        self.0.values().map(|result| (&result.fencer, result))
+    }
+
+    pub fn get(&self, fencer: &T) -> Option<&RoundFencerResult<T>> {
+        self.0.get(&fencer.get_id())
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// All fencers in ranked order, to be used as the seeding of the next round.
+    pub fn seeding(&self) -> Vec<Rc<T>> {
+        self.0
+            .values()
+            .map(|result| result.fencer.clone())
+            .collect()
+    }
+
+    /// Rounded up, fencers tied with the last one promoted also go through.
+    pub fn promoted(&self, percent: usize) -> Vec<Rc<T>> {
+        let count = (self.0.len() * percent.min(100)).div_ceil(100);
+        let Some((_, last_promoted)) = count.checked_sub(1).and_then(|i| self.0.get_index(i))
+        else {
+            return Vec::new();
+        };
+
+        self.0
+            .iter()
+            .enumerate()
+            .take_while(|(index, (_, result))| {
+                *index < count || (*result).cmp(last_promoted) == Ordering::Equal
+            })
+            .map(|(_, (_, result))| result.fencer.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RoundResults;
+    use crate::{
+        fencer::{Fencer, SimpleFencer},
+        pools::{
+            test_util::{every_bout, pool_with_scores},
+            PoolResults,
+        },
+    };
+
+    /// Pool where the earlier fencers always win 5 to `losing_score`.
+    fn finished_pool(names: &[&str], losing_score: u8) -> PoolResults<SimpleFencer> {
+        let fencers: Vec<SimpleFencer> = names
+            .iter()
+            .map(|name| SimpleFencer::new(name, name))
+            .collect();
+        let scores = every_bout(fencers.len(), |_, _| (5, losing_score));
+        pool_with_scores(&fencers, &scores).finish().unwrap()
+    }
+
+    fn names(results: &RoundResults<SimpleFencer>) -> Vec<String> {
+        results
+            .iter()
+            .map(|(fencer, result)| format!("{} {}", result.place(), fencer.get_fullname()))
+            .collect()
+    }
+
+    #[test]
+    fn ranked_by_victory_ratio() {
+        // A3 and B2 both have 2 victories but B2 fenced fewer bouts.
+        let pool_a = finished_pool(&["A1", "A2", "A3", "A4", "A5"], 0);
+        let pool_b = finished_pool(&["B1", "B2", "B3", "B4"], 0);
+
+        let results = RoundResults::new(&[pool_a, pool_b]);
+
+        assert_eq!(
+            names(&results),
+            vec!["1 A1", "2 B1", "3 A2", "4 B2", "5 A3", "6 B3", "7 A4", "8 B4", "9 A5"]
+        );
+    }
+
+    #[test]
+    fn ties_across_pools() {
+        let pool_a = finished_pool(&["A1", "A2", "A3", "A4"], 2);
+        let pool_b = finished_pool(&["B1", "B2", "B3", "B4"], 2);
+
+        let results = RoundResults::new(&[pool_a, pool_b]);
+
+        assert_eq!(
+            names(&results),
+            vec!["1T A1", "1T B1", "3T A2", "3T B2", "5T A3", "5T B3", "7T A4", "7T B4"]
+        );
+    }
+
+    #[test]
+    fn promotion_cut() {
+        let pool_a = finished_pool(&["A1", "A2", "A3", "A4", "A5"], 0);
+        let pool_b = finished_pool(&["B1", "B2", "B3", "B4"], 0);
+        let results = RoundResults::new(&[pool_a, pool_b]);
+
+        let promoted: Vec<String> = results
+            .promoted(50)
+            .iter()
+            .map(|fencer| fencer.get_fullname())
+            .collect();
+        assert_eq!(promoted, vec!["A1", "B1", "A2", "B2", "A3"]);
+        assert_eq!(results.promoted(100).len(), 9);
+        assert!(results.promoted(0).is_empty());
+
+        let pool_a = finished_pool(&["A1", "A2", "A3", "A4"], 2);
+        let pool_b = finished_pool(&["B1", "B2", "B3", "B4"], 2);
+        let results = RoundResults::new(&[pool_a, pool_b]);
+        // The cut falls between the tied A1 and B1, so both advance.
+        assert_eq!(results.promoted(10).len(), 2);
+    }
+}
@@ -1,6 +1,5 @@
-use std::{borrow::Borrow, hash::RandomState, rc::Rc};
+use std::{borrow::Borrow, rc::Rc};
 
-use indexmap::IndexMap;
 use serde::{
     ser::{Error, SerializeMap, SerializeStruct, SerializeTupleStruct},
     Serialize, Serializer,
@@ -10,100 +9,104 @@ use super::{PoolSheetBout, PoolSheetMap, PoolSheetVersus};
 
 use crate::fencer::Fencer;
 
-pub(crate) struct PoolSheetSpecialVs<T: Fencer>(PoolSheetVersus<T>);
-pub(crate) struct PoolSheetSpecialBout<T: Fencer>(PoolSheetBout<T>);
-pub(crate) struct PoolSheetSpecialFencers<T: Fencer>(Box<[Rc<T>]>);
-pub(crate) struct PoolSheetSpecialBoutsList<T: Fencer>(
-    IndexMap<PoolSheetVersus<T>, PoolSheetBout<T>, RandomState>,
-);
+/// Fencers are keyed by their 1-indexed position so the output is stable.
+fn position<T: Fencer>(fencers: &[Rc<T>], fencer: &Rc<T>) -> Option<usize> {
+    fencers
+        .iter()
+        .position(|pool_fencer| pool_fencer.get_id() == fencer.get_id())
+        .map(|index| index + 1)
+}
+
+pub(crate) struct PoolSheetSpecialVs(usize, usize);
+pub(crate) struct PoolSheetSpecialBout<'a, T: Fencer>(&'a PoolSheetBout<T>);
+pub(crate) struct PoolSheetSpecialFencers<'a, T: Fencer>(&'a [Rc<T>]);
+pub(crate) struct PoolSheetSpecialBoutsList<'a, T: Fencer> {
+    fencers: &'a [Rc<T>],
+    bouts: &'a PoolSheetMap<T>,
+}
 
-impl<T: Fencer> From<PoolSheetVersus<T>> for PoolSheetSpecialVs<T> {
-    fn from(value: PoolSheetVersus<T>) -> Self {
-        PoolSheetSpecialVs(value)
+impl PoolSheetSpecialVs {
+    fn new<T: Fencer>(fencers: &[Rc<T>], vs: &PoolSheetVersus<T>) -> Option<Self> {
+        Some(PoolSheetSpecialVs(
+            position(fencers, &vs.0)?,
+            position(fencers, &vs.1)?,
+        ))
     }
 }
 
-impl<T: Fencer + Serialize> Serialize for PoolSheetSpecialVs<T> {
+impl Serialize for PoolSheetSpecialVs {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let vs = &self.0;
-        let id1 = Rc::as_ptr(&vs.0) as usize;
-        let id2 = Rc::as_ptr(&vs.1) as usize;
         let mut state = serializer.serialize_tuple_struct("FencerVs", 2)?;
-        state.serialize_field(&id1)?;
-        state.serialize_field(&id2)?;
+        state.serialize_field(&self.0)?;
+        state.serialize_field(&self.1)?;
         state.end()
     }
 }
 
-impl<T: Fencer + Serialize> Serialize for PoolSheetSpecialBout<T> {
+impl<T: Fencer + Serialize> Serialize for PoolSheetSpecialBout<'_, T> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("PoolBout", 3)?;
+        let mut state = serializer.serialize_struct("PoolBout", 6)?;
         state.serialize_field("scores", &self.0.scores)?;
         state.serialize_field("cards", &self.0.cards)?;
         state.serialize_field("priority", &self.0.priority)?;
+        state.serialize_field("ended_on_time", &self.0.ended_on_time)?;
+        state.serialize_field("events", &self.0.events)?;
+        state.serialize_field("winner", &self.0.manual_winner())?;
         state.end()
     }
 }
 
-impl<T: Fencer + Serialize> From<PoolSheetBout<T>> for PoolSheetSpecialBout<T> {
-    fn from(value: PoolSheetBout<T>) -> Self {
+impl<'a, T: Fencer + Serialize> From<&'a PoolSheetBout<T>> for PoolSheetSpecialBout<'a, T> {
+    fn from(value: &'a PoolSheetBout<T>) -> Self {
         PoolSheetSpecialBout(value)
     }
 }
 
-impl<T: Fencer + Serialize> Serialize for PoolSheetSpecialFencers<T> {
+impl<T: Fencer + Serialize> Serialize for PoolSheetSpecialFencers<'_, T> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let fencers: &[Rc<T>] = self.0.borrow();
+        let fencers = self.0;
         let mut map = serializer.serialize_map(Some(fencers.len()))?;
-        for fencer in fencers {
-            let pointer = Rc::as_ptr(fencer) as usize;
-            map.serialize_entry::<str, T>(&pointer.to_string(), fencer.borrow())?;
+        for (index, fencer) in fencers.iter().enumerate() {
+            map.serialize_entry::<str, T>(&(index + 1).to_string(), fencer.borrow())?;
         }
         map.end()
     }
 }
 
-impl<T: Fencer + Serialize> From<Box<[Rc<T>]>> for PoolSheetSpecialFencers<T> {
-    fn from(value: Box<[Rc<T>]>) -> Self {
+impl<'a, T: Fencer + Serialize> From<&'a [Rc<T>]> for PoolSheetSpecialFencers<'a, T> {
+    fn from(value: &'a [Rc<T>]) -> Self {
         PoolSheetSpecialFencers(value)
     }
 }
 
-// struct SpecialError;
-// impl Error for SpecialError {
-//     fn custom<T>(msg:T) -> Self where T:Display {
-
-//     }
-// }
+impl<'a, T: Fencer> PoolSheetSpecialBoutsList<'a, T> {
+    pub(crate) fn new(fencers: &'a [Rc<T>], bouts: &'a PoolSheetMap<T>) -> Self {
+        PoolSheetSpecialBoutsList { fencers, bouts }
+    }
+}
 
-impl<T: Fencer + Serialize> Serialize for PoolSheetSpecialBoutsList<T> {
+impl<T: Fencer + Serialize> Serialize for PoolSheetSpecialBoutsList<'_, T> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let bout_list = &self.0;
-        let mut map = serializer.serialize_map(Some(bout_list.len()))?;
-        for (key, val) in bout_list {
-            let new_key = serde_json::to_string(&PoolSheetSpecialVs::from(key.clone()))
+        let mut map = serializer.serialize_map(Some(self.bouts.len()))?;
+        for (key, val) in self.bouts {
+            let special_vs = PoolSheetSpecialVs::new(self.fencers, key)
+                .ok_or_else(|| Error::custom("Bout has a fencer that is not in the pool"))?;
+            let new_key = serde_json::to_string(&special_vs)
                 .map_err(|err| Error::custom(format!("Error Creating the BoutList Key {err:?}")))?;
-            let new_val = PoolSheetSpecialBout::from(val.clone());
-            map.serialize_entry(&new_key, &new_val)?;
+            map.serialize_entry(&new_key, &PoolSheetSpecialBout::from(val))?;
         }
         map.end()
     }
 }
-
-impl<T: Fencer + Serialize> From<PoolSheetMap<T>> for PoolSheetSpecialBoutsList<T> {
-    fn from(value: PoolSheetMap<T>) -> Self {
-        PoolSheetSpecialBoutsList(value)
-    }
-}
@@ -0,0 +1,44 @@
+use indexmap::IndexSet;
+
+use crate::{bout::FencerScore, cards::Cards, fencer::SimpleFencer};
+
+use super::{bout_creation::SimpleBoutsCreator, PoolSheet};
+
+/// Fencer1, Fencer2, ...
+pub(crate) fn fencers(count: usize) -> Vec<SimpleFencer> {
+    (1..=count)
+        .map(|i| SimpleFencer::new(format!("Fencer{i}"), format!("Fencer{i}")))
+        .collect()
+}
+
+/// Scores are `(a, b, score_a, score_b)` with fencers by index.
+pub(crate) fn pool_with_scores(
+    fencers: &[SimpleFencer],
+    scores: &[(usize, usize, u8, u8)],
+) -> PoolSheet<SimpleFencer> {
+    let mut pool_sheet =
+        PoolSheet::new(IndexSet::from_iter(fencers.to_vec()), &SimpleBoutsCreator).unwrap();
+    for &(a, b, score_a, score_b) in scores {
+        pool_sheet
+            .update_score(
+                FencerScore::new(fencers[a].clone(), score_a, Cards::default()),
+                FencerScore::new(fencers[b].clone(), score_b, Cards::default()),
+            )
+            .unwrap();
+    }
+    pool_sheet
+}
+
+/// Scores for every bout in a pool of `count`, `score` is given the indexes with `a < b`.
+pub(crate) fn every_bout(
+    count: usize,
+    score: impl Fn(usize, usize) -> (u8, u8),
+) -> Vec<(usize, usize, u8, u8)> {
+    (0..count)
+        .flat_map(|a| ((a + 1)..count).map(move |b| (a, b)))
+        .map(|(a, b)| {
+            let (score_a, score_b) = score(a, b);
+            (a, b, score_a, score_b)
+        })
+        .collect()
+}
@@ -0,0 +1,344 @@
+use std::fmt::Debug;
+
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::bout::{FencerVs, TuplePos};
+use crate::fencer::Fencer;
+
+use super::PoolSheet;
+
+/// How a group of fencers tied on victories and indicator was resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TieBreakMethod {
+    LeftTied,
+    SeededRandom(u64),
+    HeadToHead,
+    TouchesScored,
+    CoinToss,
+}
+
+/// Fencers are referred to by their 1-indexed position in the pool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TieResolution {
+    method: TieBreakMethod,
+    /// Groups of fencers from best to worst, a group with more than one fencer is still tied.
+    order: Vec<Vec<usize>>,
+}
+
+impl TieResolution {
+    pub fn new(method: TieBreakMethod, order: Vec<Vec<usize>>) -> Self {
+        TieResolution { method, order }
+    }
+
+    pub fn left_tied(tied: &[usize]) -> Self {
+        TieResolution::new(TieBreakMethod::LeftTied, vec![tied.to_vec()])
+    }
+
+    /// A coin toss, `order` is the positions from best to worst.
+    pub fn coin_toss(order: Vec<usize>) -> Self {
+        TieResolution::new(
+            TieBreakMethod::CoinToss,
+            order.into_iter().map(|position| vec![position]).collect(),
+        )
+    }
+
+    pub fn method(&self) -> TieBreakMethod {
+        self.method
+    }
+
+    pub fn order(&self) -> &[Vec<usize>] {
+        &self.order
+    }
+
+    /// Checks that this resolution orders exactly the fencers in `tied`.
+    pub(crate) fn resolves(&self, tied: &[usize]) -> bool {
+        let mut positions: Vec<usize> = self.order.iter().flatten().copied().collect();
+        let mut tied = tied.to_vec();
+        positions.sort();
+        tied.sort();
+        positions == tied
+    }
+}
+
+/// Decides the order of fencers that are tied on victories and indicator in a pool.
+pub trait TieBreaker<T: Fencer> {
+    /// `tied` holds the 1-indexed pool positions of the tied fencers.
+    fn break_tie(&mut self, tied: &[usize], poolsheet: &PoolSheet<T>) -> TieResolution;
+}
+
+impl<T: Fencer, B: TieBreaker<T> + ?Sized> TieBreaker<T> for &mut B {
+    fn break_tie(&mut self, tied: &[usize], poolsheet: &PoolSheet<T>) -> TieResolution {
+        (**self).break_tie(tied, poolsheet)
+    }
+}
+
+/// Leaves every tie as a tie.
+pub struct LeaveTied;
+
+impl<T: Fencer> TieBreaker<T> for LeaveTied {
+    fn break_tie(&mut self, tied: &[usize], _poolsheet: &PoolSheet<T>) -> TieResolution {
+        TieResolution::left_tied(tied)
+    }
+}
+
+/// The same seed always gives the same order.
+pub struct SeededTieBreaker {
+    seed: u64,
+    rng: ChaCha8Rng,
+}
+
+impl SeededTieBreaker {
+    pub fn new(seed: u64) -> Self {
+        SeededTieBreaker {
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl<T: Fencer> TieBreaker<T> for SeededTieBreaker {
+    fn break_tie(&mut self, tied: &[usize], _poolsheet: &PoolSheet<T>) -> TieResolution {
+        let mut order = tied.to_vec();
+        order.shuffle(&mut self.rng);
+        TieResolution::new(
+            TieBreakMethod::SeededRandom(self.seed),
+            order.into_iter().map(|position| vec![position]).collect(),
+        )
+    }
+}
+
+/// Head to head for two fencers, touches scored for more.
+pub struct HeadToHead;
+
+impl<T: Fencer + Debug> TieBreaker<T> for HeadToHead {
+    fn break_tie(&mut self, tied: &[usize], poolsheet: &PoolSheet<T>) -> TieResolution {
+        if let [a, b] = tied {
+            let fencer_a = &poolsheet.fencers[a - 1];
+            let fencer_b = &poolsheet.fencers[b - 1];
+            let winner = FencerVs::new(fencer_a.clone(), fencer_b.clone())
+                .ok()
+                .and_then(|vs| poolsheet.get_bout(&vs).ok())
+                .and_then(|bout| bout.get_winner());
+
+            return match winner {
+                Some(winner) if winner.get_id() == fencer_a.get_id() => {
+                    TieResolution::new(TieBreakMethod::HeadToHead, vec![vec![*a], vec![*b]])
+                }
+                Some(_) => TieResolution::new(TieBreakMethod::HeadToHead, vec![vec![*b], vec![*a]]),
+                None => TieResolution::left_tied(tied),
+            };
+        }
+
+        let mut by_touches: Vec<(usize, u16)> = tied
+            .iter()
+            .map(|position| (*position, touches_scored(*position, poolsheet)))
+            .collect();
+        by_touches.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+        let mut order: Vec<Vec<usize>> = Vec::new();
+        let mut last_touches = None;
+        for (position, touches) in by_touches {
+            match order.last_mut() {
+                Some(group) if last_touches == Some(touches) => group.push(position),
+                _ => order.push(vec![position]),
+            }
+            last_touches = Some(touches);
+        }
+
+        if order.len() == 1 {
+            TieResolution::left_tied(tied)
+        } else {
+            TieResolution::new(TieBreakMethod::TouchesScored, order)
+        }
+    }
+}
+
+fn touches_scored<T: Fencer + Debug>(position: usize, poolsheet: &PoolSheet<T>) -> u16 {
+    let fencer = poolsheet.fencers[position - 1].as_ref();
+    poolsheet
+        .iter_bouts()
+        .filter(|(_, bout)| poolsheet.counts(bout))
+        .filter_map(|(_, bout)| {
+            let (touches_a, touches_b) = bout.touches();
+            match bout.fencers.pos(fencer) {
+                TuplePos::First => Some(touches_a),
+                TuplePos::Second => Some(touches_b),
+                TuplePos::None => None,
+            }
+        })
+        .map(u16::from)
+        .sum()
+}
+
+/// Recorded resolutions, eg. coin tosses, with a fallback for the rest.
+pub struct RecordedTieBreaker<B> {
+    recorded: Vec<TieResolution>,
+    fallback: B,
+}
+
+impl<B> RecordedTieBreaker<B> {
+    pub fn new(recorded: Vec<TieResolution>, fallback: B) -> Self {
+        RecordedTieBreaker { recorded, fallback }
+    }
+}
+
+impl<T: Fencer, B: TieBreaker<T>> TieBreaker<T> for RecordedTieBreaker<B> {
+    fn break_tie(&mut self, tied: &[usize], poolsheet: &PoolSheet<T>) -> TieResolution {
+        match self
+            .recorded
+            .iter()
+            .find(|resolution| resolution.resolves(tied))
+        {
+            Some(resolution) => resolution.clone(),
+            None => self.fallback.break_tie(tied, poolsheet),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HeadToHead, LeaveTied, SeededTieBreaker, TieBreakMethod, TieResolution};
+    use crate::{
+        fencer::{Fencer, SimpleFencer},
+        pools::{
+            test_util::{fencers, pool_with_scores},
+            PoolResults, PoolSheet,
+        },
+    };
+
+    /// Fencer1 and Fencer2 tie on 2 victories and +5, Fencer1 won the bout between them.
+    fn tied_pool() -> PoolSheet<SimpleFencer> {
+        pool_with_scores(
+            &fencers(4),
+            &[
+                (0, 1, 5, 4),
+                (0, 2, 4, 5),
+                (0, 3, 5, 0),
+                (1, 2, 5, 0),
+                (1, 3, 5, 4),
+                (2, 3, 3, 5),
+            ],
+        )
+    }
+
+    fn places(results: &PoolResults<SimpleFencer>) -> Vec<String> {
+        results
+            .iter()
+            .map(|(fencer, result)| format!("{} {}", result.place(), fencer.get_fullname()))
+            .collect()
+    }
+
+    #[test]
+    fn leave_tied() {
+        let results = tied_pool().finish_with(&mut LeaveTied).unwrap();
+        assert_eq!(
+            places(&results),
+            vec!["1T Fencer1", "1T Fencer2", "3 Fencer4", "4 Fencer3"]
+        );
+        assert_eq!(
+            results.tie_resolutions(),
+            &[TieResolution::left_tied(&[1, 2])]
+        );
+    }
+
+    #[test]
+    fn head_to_head_by_default() {
+        let results = tied_pool().finish().unwrap();
+        assert_eq!(
+            places(&results),
+            vec!["1 Fencer1", "2 Fencer2", "3 Fencer4", "4 Fencer3"]
+        );
+        assert_eq!(
+            results.tie_resolutions()[0].method(),
+            TieBreakMethod::HeadToHead
+        );
+    }
+
+    #[test]
+    fn three_way_tie_by_touches_scored() {
+        // Fencer1, Fencer2 and Fencer3 beat each other in a circle and are all on 2 victories and +2.
+        let pool_sheet = pool_with_scores(
+            &fencers(4),
+            &[
+                (0, 1, 5, 2),
+                (1, 2, 5, 4),
+                (2, 0, 5, 3),
+                (0, 3, 5, 4),
+                (1, 3, 5, 1),
+                (2, 3, 5, 4),
+            ],
+        );
+        let results = pool_sheet.finish().unwrap();
+        assert_eq!(
+            places(&results),
+            vec!["1 Fencer3", "2 Fencer1", "3 Fencer2", "4 Fencer4"]
+        );
+        assert_eq!(
+            results.tie_resolutions()[0].method(),
+            TieBreakMethod::TouchesScored
+        );
+
+        // Same touches scored all around, so it stays a tie.
+        let pool_sheet = pool_with_scores(
+            &fencers(4),
+            &[
+                (0, 1, 5, 3),
+                (1, 2, 5, 3),
+                (2, 0, 5, 3),
+                (0, 3, 5, 0),
+                (1, 3, 5, 0),
+                (2, 3, 5, 0),
+            ],
+        );
+        let results = pool_sheet.finish().unwrap();
+        assert_eq!(
+            places(&results),
+            vec!["1T Fencer1", "1T Fencer2", "1T Fencer3", "4 Fencer4"]
+        );
+    }
+
+    #[test]
+    fn seeded_is_reproducible() {
+        let pool_sheet = tied_pool();
+        let first = pool_sheet
+            .finish_with(&mut SeededTieBreaker::new(2024))
+            .unwrap();
+        for _ in 0..10 {
+            let again = pool_sheet
+                .finish_with(&mut SeededTieBreaker::new(2024))
+                .unwrap();
+            assert_eq!(places(&first), places(&again));
+        }
+        // Pinned so a change of generator is noticed.
+        assert_eq!(
+            places(&first),
+            vec!["1 Fencer2", "2 Fencer1", "3 Fencer4", "4 Fencer3"]
+        );
+        assert_eq!(
+            first.tie_resolutions()[0].method(),
+            TieBreakMethod::SeededRandom(2024)
+        );
+    }
+
+    #[test]
+    fn stored_resolution_survives_serialization() {
+        let mut pool_sheet = tied_pool();
+        pool_sheet.set_tie_resolutions(vec![TieResolution::coin_toss(vec![2, 1])]);
+
+        let json = serde_json::to_string(&pool_sheet).unwrap();
+        let pool_sheet: PoolSheet<SimpleFencer> = serde_json::from_str(&json).unwrap();
+
+        // The stored coin toss is used before the tie breaker passed in.
+        let results = pool_sheet.finish_with(&mut HeadToHead).unwrap();
+        assert_eq!(
+            places(&results),
+            vec!["1 Fencer2", "2 Fencer1", "3 Fencer4", "4 Fencer3"]
+        );
+        assert_eq!(
+            results.tie_resolutions(),
+            &[TieResolution::coin_toss(vec![2, 1])]
+        );
+    }
+}
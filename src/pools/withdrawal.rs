@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+/// Why a fencer stopped fencing partway through a pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WithdrawalReason {
+    /// Injured or ill, the bouts already fenced still count.
+    Medical,
+    /// Withdrew for any other reason.
+    Abandoned,
+    /// Black carded or otherwise excluded from the competition.
+    Excluded,
+}
+
+impl WithdrawalReason {
+    /// Only a medical withdrawal keeps the finished bouts.
+    pub fn annuls_results(&self) -> bool {
+        !matches!(self, WithdrawalReason::Medical)
+    }
+}
+
+/// A withdrawn fencer, referred to by their 1-indexed position in the pool like tie resolutions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Withdrawal {
+    position: usize,
+    reason: WithdrawalReason,
+}
+
+impl Withdrawal {
+    pub fn new(position: usize, reason: WithdrawalReason) -> Self {
+        Withdrawal { position, reason }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn reason(&self) -> WithdrawalReason {
+        self.reason
+    }
+}
@@ -0,0 +1,197 @@
+//! Orders an entry list before an event, the seed order is used to fill the pools and the tableau.
+
+use std::fmt::Debug;
+
+use indexmap::{IndexMap, IndexSet};
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    fencer::{Affiliated, Fencer},
+    organizations::usafencing::Rating,
+    pools::{bout_creation::BoutsCreator, PoolRound, PoolSheetError},
+    tableau::{Tableau, TableauError},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+struct SeedEntry<T: Fencer> {
+    fencer: T,
+    rating: Rating,
+    points: f64,
+}
+
+/// Seeded by rating, then points, then a seeded draw.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Seeding<T: Fencer> {
+    entries: IndexMap<T::Id, SeedEntry<T>>,
+}
+
+impl<T: Fencer> Default for Seeding<T> {
+    fn default() -> Self {
+        Seeding {
+            entries: IndexMap::new(),
+        }
+    }
+}
+
+impl<T: Fencer> Seeding<T> {
+    pub fn new() -> Seeding<T> {
+        Seeding::default()
+    }
+
+    /// Adding a fencer again replaces them.
+    pub fn add(&mut self, fencer: T, rating: Rating, points: f64) {
+        self.entries.insert(
+            fencer.get_id(),
+            SeedEntry {
+                fencer,
+                rating,
+                points,
+            },
+        );
+    }
+
+    /// Adds a fencer with no points.
+    pub fn add_rated(&mut self, fencer: T, rating: Rating) {
+        self.add(fencer, rating, 0.0);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Ratings as they stand in `season`, `draw_seed` orders the ties.
+    pub fn seed(&self, season: usize, draw_seed: u64) -> IndexSet<T> {
+        let mut entries: Vec<&SeedEntry<T>> = self.entries.values().collect();
+        entries.shuffle(&mut ChaCha8Rng::seed_from_u64(draw_seed));
+        // Stable sort, so the draw only decides between fencers tied on both.
+        entries.sort_by(|a, b| {
+            b.rating
+                .in_season(season)
+                .cmp(&a.rating.in_season(season))
+                .then_with(|| b.points.total_cmp(&a.points))
+        });
+        entries
+            .into_iter()
+            .map(|entry| entry.fencer.clone())
+            .collect()
+    }
+}
+
+impl<T: Fencer + Debug> Seeding<T> {
+    /// Seeds the event straight into a tableau.
+    pub fn tableau(&self, season: usize, draw_seed: u64) -> Result<Tableau<T>, TableauError> {
+        Tableau::new(self.seed(season, draw_seed))
+    }
+}
+
+impl<T: Fencer + Affiliated + Debug> Seeding<T> {
+    /// Seeds the event into a round of pools.
+    pub fn pools<C>(
+        &self,
+        creator: &C,
+        season: usize,
+        draw_seed: u64,
+    ) -> Result<PoolRound<T>, PoolSheetError>
+    where
+        C: BoutsCreator<T>,
+    {
+        PoolRound::new(self.seed(season, draw_seed), creator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fencer::{Fencer, SimpleFencer},
+        organizations::usafencing::Rating,
+        pools::bout_creation::SimpleBoutsCreator,
+    };
+
+    use super::Seeding;
+
+    fn names(seeding: &Seeding<SimpleFencer>, draw_seed: u64) -> Vec<String> {
+        seeding
+            .seed(2024, draw_seed)
+            .iter()
+            .map(|fencer| fencer.get_fullname())
+            .collect()
+    }
+
+    #[test]
+    fn rating_then_points_then_draw() {
+        let mut seeding = Seeding::new();
+        seeding.add(SimpleFencer::new("C21", "C21"), Rating::C(2021), 0.0);
+        seeding.add(
+            SimpleFencer::new("B24 few points", "B24 few points"),
+            Rating::B(2024),
+            3.5,
+        );
+        seeding.add(
+            SimpleFencer::new("B24 more points", "B24 more points"),
+            Rating::B(2024),
+            12.0,
+        );
+        seeding.add_rated(SimpleFencer::new("A21", "A21"), Rating::A(2021));
+        // An A19 is a B in 2024 and an A15 has expired to unrated.
+        seeding.add_rated(SimpleFencer::new("A19", "A19"), Rating::A(2019));
+        seeding.add_rated(SimpleFencer::new("A15", "A15"), Rating::A(2015));
+        for i in 1..=6 {
+            seeding.add_rated(
+                SimpleFencer::new(format!("U{i}"), format!("U{i}")),
+                Rating::NoRating,
+            );
+        }
+        seeding.add(
+            SimpleFencer::new("U points", "U points"),
+            Rating::NoRating,
+            1.0,
+        );
+
+        let order = names(&seeding, 7);
+        assert_eq!(
+            order[..6],
+            [
+                "A21",
+                "B24 more points",
+                "B24 few points",
+                "A19",
+                "C21",
+                "U points"
+            ]
+        );
+        assert!(order[6..].contains(&"A15".to_string()));
+        assert_eq!(names(&seeding, 7), order);
+        assert!((0..20).any(|draw_seed| names(&seeding, draw_seed)[6..] != order[6..]));
+    }
+
+    #[test]
+    fn seed_order_fills_pools_and_tableau() {
+        let mut seeding = Seeding::new();
+        for i in 1..=8 {
+            seeding.add(
+                SimpleFencer::new(i, format!("Fencer{i}")),
+                Rating::NoRating,
+                f64::from(i),
+            );
+        }
+
+        let tableau = seeding.tableau(2024, 0).unwrap();
+        assert_eq!(tableau.get_seeding()[0].get_fullname(), "Fencer8");
+
+        let round = seeding.pools(&SimpleBoutsCreator, 2024, 0).unwrap();
+        assert_eq!(round.get_pools().len(), 2);
+        assert_eq!(
+            round.get_pools()[0].get_fencers()[0].get_fullname(),
+            "Fencer8"
+        );
+        assert_eq!(
+            round.get_pools()[1].get_fencers()[0].get_fullname(),
+            "Fencer7"
+        );
+    }
+}
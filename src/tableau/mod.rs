@@ -0,0 +1,519 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+use indexmap::IndexSet;
+
+use crate::bout::{Bout, BoutEventKind, BoutFormat, FencerScore, FencerVs};
+use crate::fencer::Fencer;
+use crate::pools::{Placement, PoolResults, RoundResults};
+
+mod tableau_error;
+pub use tableau_error::TableauError;
+
+pub type TableauBout<T> = Bout<T, Rc<T>>;
+
+/// A position in a table of the tableau.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableauSlot<T: Fencer> {
+    /// Waiting on the result of a bout in the previous table.
+    Waiting,
+    /// Nobody is in this position, the opponent advances without fencing.
+    Bye,
+    Fencer(Rc<T>),
+}
+
+/// A single round of the tableau, eg. the table of 16.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table<T: Fencer> {
+    size: usize,
+    slots: Box<[TableauSlot<T>]>,
+    bouts: Box<[Option<TableauBout<T>>]>,
+    format: BoutFormat,
+}
+
+impl<T: Fencer> Table<T> {
+    fn new(size: usize, format: BoutFormat) -> Table<T> {
+        Table {
+            size,
+            slots: vec![TableauSlot::Waiting; size].into_boxed_slice(),
+            bouts: vec![None; size / 2].into_boxed_slice(),
+            format,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn slots(&self) -> &[TableauSlot<T>] {
+        &self.slots
+    }
+
+    /// `None` for a bye or a bout still waiting on the previous table.
+    pub fn get_bout(&self, index: usize) -> Option<&TableauBout<T>> {
+        self.bouts.get(index)?.as_ref()
+    }
+
+    pub fn iter_bouts(&self) -> impl Iterator<Item = &TableauBout<T>> {
+        self.bouts.iter().flatten()
+    }
+
+    pub fn get_format(&self) -> &BoutFormat {
+        &self.format
+    }
+
+    fn set_format(&mut self, format: BoutFormat) {
+        self.format = format;
+        for bout in self.bouts.iter_mut().flatten() {
+            bout.set_format(format);
+        }
+    }
+
+    fn set_slot(&mut self, index: usize, slot: TableauSlot<T>) {
+        if self.slots[index] == slot {
+            return;
+        }
+        self.slots[index] = slot;
+
+        // The fencers changed so any result in this bout is no longer valid.
+        let bout_index = index / 2;
+        self.bouts[bout_index] =
+            match (&self.slots[2 * bout_index], &self.slots[2 * bout_index + 1]) {
+                (TableauSlot::Fencer(fencer_a), TableauSlot::Fencer(fencer_b)) => {
+                    Some(Bout::with_format(
+                        FencerVs::new(fencer_a.clone(), fencer_b.clone())
+                            .expect("A fencer should only have one position in a table"),
+                        self.format,
+                    ))
+                }
+                _ => None,
+            };
+    }
+
+    /// What moves on to the next table from the bout at `index`.
+    fn advancing(&self, index: usize) -> TableauSlot<T> {
+        match (&self.slots[2 * index], &self.slots[2 * index + 1]) {
+            (TableauSlot::Fencer(fencer), TableauSlot::Bye)
+            | (TableauSlot::Bye, TableauSlot::Fencer(fencer)) => {
+                TableauSlot::Fencer(fencer.clone())
+            }
+            (TableauSlot::Bye, TableauSlot::Bye) => TableauSlot::Bye,
+            (TableauSlot::Fencer(_), TableauSlot::Fencer(_)) => {
+                match self.bouts[index].as_ref().and_then(bout_result) {
+                    Some((winner, _)) => TableauSlot::Fencer(winner),
+                    None => TableauSlot::Waiting,
+                }
+            }
+            _ => TableauSlot::Waiting,
+        }
+    }
+}
+
+/// Returns the winner and loser of a bout if it has been decided.
+fn bout_result<T: Fencer>(bout: &TableauBout<T>) -> Option<(Rc<T>, Rc<T>)> {
+    let winner = bout.get_winner()?;
+    let (fencer_a, fencer_b) = bout.get_fencers_owned();
+    if fencer_a.get_id() == winner.get_id() {
+        Some((fencer_a, fencer_b))
+    } else {
+        Some((fencer_b, fencer_a))
+    }
+}
+
+/// Seeds in bracket order, `size` should be a power of two.
+pub fn seed_positions(size: usize) -> Vec<usize> {
+    let mut positions = vec![1];
+    while positions.len() < size {
+        let sum = positions.len() * 2 + 1;
+        positions = positions
+            .iter()
+            .enumerate()
+            .flat_map(|(index, &seed)| {
+                if index % 2 == 0 {
+                    [seed, sum - seed]
+                } else {
+                    [sum - seed, seed]
+                }
+            })
+            .collect();
+    }
+    positions
+}
+
+/// Top seeds get byes when the table isn't full.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tableau<T: Fencer> {
+    seeding: Box<[Rc<T>]>,
+    tables: Vec<Table<T>>,
+}
+
+impl<T: Fencer + Debug> Tableau<T> {
+    /// Creates a tableau from fencers in seed order.
+    pub fn new(seeding: IndexSet<T>) -> Result<Tableau<T>, TableauError> {
+        Tableau::from_seeding(seeding.into_iter().map(Rc::new).collect())
+    }
+
+    /// Creates a tableau seeded from the results of one or more pools.
+    pub fn from_pool_results(results: &[PoolResults<T>]) -> Result<Tableau<T>, TableauError> {
+        Tableau::from_round_results(&RoundResults::new(results))
+    }
+
+    /// Creates a tableau seeded by the overall ranking of a round of pools.
+    pub fn from_round_results(results: &RoundResults<T>) -> Result<Tableau<T>, TableauError> {
+        Tableau::from_seeding(results.seeding())
+    }
+
+    pub(crate) fn from_seeding(seeding: Vec<Rc<T>>) -> Result<Tableau<T>, TableauError> {
+        if seeding.len() < 2 {
+            return Err(TableauError::NotEnoughFencers);
+        }
+
+        let size = seeding.len().next_power_of_two();
+        let mut tables = Vec::new();
+        let mut table_size = size;
+        while table_size >= 2 {
+            tables.push(Table::new(table_size, BoutFormat::direct_elimination()));
+            table_size /= 2;
+        }
+
+        for (index, seed) in seed_positions(size).into_iter().enumerate() {
+            let slot = match seeding.get(seed - 1) {
+                Some(fencer) => TableauSlot::Fencer(fencer.clone()),
+                None => TableauSlot::Bye,
+            };
+            tables[0].set_slot(index, slot);
+        }
+
+        let mut tableau = Tableau {
+            seeding: seeding.into_boxed_slice(),
+            tables,
+        };
+        tableau.advance(0);
+        Ok(tableau)
+    }
+
+    pub fn get_seeding(&self) -> Vec<&T> {
+        self.seeding.iter().map(|x| x.as_ref()).collect()
+    }
+
+    /// Tables from the largest to the final.
+    pub fn tables(&self) -> &[Table<T>] {
+        &self.tables
+    }
+
+    pub fn get_table(&self, size: usize) -> Option<&Table<T>> {
+        self.tables.iter().find(|table| table.size == size)
+    }
+
+    pub fn get_bout<U: Borrow<T> + Clone>(
+        &self,
+        vs: &FencerVs<T, U>,
+    ) -> Result<&TableauBout<T>, TableauError> {
+        let (table_index, bout_index) = self.find_bout(vs.0.borrow(), vs.1.borrow())?;
+        Ok(self.tables[table_index].bouts[bout_index]
+            .as_ref()
+            .expect("Bout was found"))
+    }
+
+    /// Sets the format of every bout in the tableau, bouts are fenced to 15 touches by default.
+    pub fn set_format(&mut self, format: BoutFormat) {
+        for table in self.tables.iter_mut() {
+            table.set_format(format);
+        }
+    }
+
+    /// Format for one table, eg. a table of 64 fenced to 10.
+    pub fn set_table_format(
+        &mut self,
+        size: usize,
+        format: BoutFormat,
+    ) -> Result<(), TableauError> {
+        self.tables
+            .iter_mut()
+            .find(|table| table.size == size)
+            .ok_or(TableauError::NoTableFound)?
+            .set_format(format);
+        Ok(())
+    }
+
+    /// Moves the winner on, later bouts that depended on a changed result are reset.
+    pub fn update_score<U: Borrow<T>>(
+        &mut self,
+        fencer_a: FencerScore<T, U>,
+        fencer_b: FencerScore<T, U>,
+    ) -> Result<(), TableauError> {
+        self.set_final_score(fencer_a, fencer_b, false)
+    }
+
+    /// Same as `update_score` for a bout that ended when time ran out.
+    pub fn update_score_on_time<U: Borrow<T>>(
+        &mut self,
+        fencer_a: FencerScore<T, U>,
+        fencer_b: FencerScore<T, U>,
+    ) -> Result<(), TableauError> {
+        self.set_final_score(fencer_a, fencer_b, true)
+    }
+
+    /// Records an event in a bout, the winner moves on once the event ends the bout.
+    pub fn record_event<U: Borrow<T> + Clone>(
+        &mut self,
+        vs: &FencerVs<T, U>,
+        event: BoutEventKind,
+    ) -> Result<(), TableauError> {
+        let (table_index, bout_index) = self.find_bout(vs.0.borrow(), vs.1.borrow())?;
+        self.tables[table_index].bouts[bout_index]
+            .as_mut()
+            .expect("Bout was found")
+            .record(event)?;
+        self.advance(table_index);
+        Ok(())
+    }
+
+    fn set_final_score<U: Borrow<T>>(
+        &mut self,
+        fencer_a: FencerScore<T, U>,
+        fencer_b: FencerScore<T, U>,
+        ended_on_time: bool,
+    ) -> Result<(), TableauError> {
+        let (table_index, bout_index) =
+            self.find_bout(fencer_a.fencer.borrow(), fencer_b.fencer.borrow())?;
+        let bout = self.tables[table_index].bouts[bout_index]
+            .as_mut()
+            .expect("Bout was found");
+        if ended_on_time {
+            bout.update_scores_on_time(fencer_a, fencer_b)?;
+        } else {
+            bout.update_scores(fencer_a, fencer_b)?;
+        }
+        self.advance(table_index);
+        Ok(())
+    }
+
+    pub fn get_winner(&self) -> Option<&T> {
+        match self.tables.last()?.advancing(0) {
+            TableauSlot::Fencer(winner) => self
+                .seeding
+                .iter()
+                .find(|fencer| fencer.get_id() == winner.get_id())
+                .map(|fencer| fencer.as_ref()),
+            _ => None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.get_winner().is_some()
+    }
+
+    /// Semifinal losers tie for 3rd, earlier losers are placed by seed.
+    pub fn placements(&self) -> Result<Vec<(Rc<T>, Placement)>, TableauError> {
+        let winner = match self
+            .tables
+            .last()
+            .expect("Should have at least one table")
+            .advancing(0)
+        {
+            TableauSlot::Fencer(winner) => winner,
+            _ => return Err(TableauError::TableauNotComplete),
+        };
+
+        let mut placements = vec![(winner, Placement::Absolute(1))];
+        for table in self.tables.iter().rev() {
+            let mut losers: Vec<Rc<T>> = table
+                .iter_bouts()
+                .filter_map(bout_result)
+                .map(|(_, loser)| loser)
+                .collect();
+            losers.sort_by_key(|loser| self.seed_of(loser));
+
+            let first_place = table.size / 2 + 1;
+            for (index, loser) in losers.into_iter().enumerate() {
+                let place = if table.size == 4 {
+                    Placement::Tied(first_place)
+                } else {
+                    Placement::Absolute(first_place + index)
+                };
+                placements.push((loser, place));
+            }
+        }
+        Ok(placements)
+    }
+
+    fn seed_of(&self, fencer: &T) -> usize {
+        self.seeding
+            .iter()
+            .position(|seed| seed.get_id() == fencer.get_id())
+            .expect("Every fencer in the tableau should be seeded")
+    }
+
+    /// Finds the table and bout index the fencers meet in.
+    fn find_bout(&self, fencer_a: &T, fencer_b: &T) -> Result<(usize, usize), TableauError> {
+        let vs: FencerVs<T, &T> = FencerVs::new(fencer_a, fencer_b)?;
+        for (table_index, table) in self.tables.iter().enumerate().rev() {
+            for (bout_index, bout) in table.bouts.iter().enumerate() {
+                if let Some(bout) = bout {
+                    if bout.fencers.ref_type() == vs {
+                        return Ok((table_index, bout_index));
+                    }
+                }
+            }
+        }
+        Err(TableauError::NoBoutFound)
+    }
+
+    /// Recalculates who is in every table after `from_table`.
+    fn advance(&mut self, from_table: usize) {
+        for table_index in from_table..self.tables.len() - 1 {
+            for bout_index in 0..self.tables[table_index].size / 2 {
+                let slot = self.tables[table_index].advancing(bout_index);
+                self.tables[table_index + 1].set_slot(bout_index, slot);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexSet;
+
+    use super::{seed_positions, Tableau, TableauSlot};
+    use crate::{
+        bout::FencerScore,
+        cards::Cards,
+        fencer::Fencer,
+        pools::test_util::{every_bout, fencers, pool_with_scores},
+    };
+
+    #[test]
+    fn seed_positions_test() {
+        assert_eq!(seed_positions(2), vec![1, 2]);
+        assert_eq!(seed_positions(4), vec![1, 4, 3, 2]);
+        assert_eq!(seed_positions(8), vec![1, 8, 5, 4, 3, 6, 7, 2]);
+    }
+
+    #[test]
+    fn byes_for_top_seeds() {
+        let fencers = fencers(6);
+        let tableau = Tableau::new(IndexSet::from_iter(fencers.clone())).unwrap();
+
+        assert_eq!(tableau.tables().len(), 3);
+        let table_of_8 = tableau.get_table(8).unwrap();
+        assert_eq!(table_of_8.slots()[1], TableauSlot::Bye);
+        assert_eq!(table_of_8.slots()[6], TableauSlot::Bye);
+        assert_eq!(table_of_8.iter_bouts().count(), 2);
+
+        let table_of_4 = tableau.get_table(4).unwrap();
+        assert_eq!(
+            table_of_4.slots()[0],
+            TableauSlot::Fencer(fencers[0].clone().into())
+        );
+        assert_eq!(
+            table_of_4.slots()[3],
+            TableauSlot::Fencer(fencers[1].clone().into())
+        );
+        assert_eq!(table_of_4.slots()[1], TableauSlot::Waiting);
+    }
+
+    #[test]
+    fn not_enough_fencers() {
+        assert!(Tableau::new(IndexSet::from_iter(fencers(1))).is_err());
+    }
+
+    #[test]
+    fn fence_to_final() {
+        let fencers = fencers(6);
+        let mut tableau = Tableau::new(IndexSet::from_iter(fencers.clone())).unwrap();
+
+        // Higher seed wins every bout except 5 upsetting 4.
+        let bouts = [(4, 5, 12, 15), (3, 6, 15, 7), (1, 5, 15, 9), (3, 2, 14, 15)];
+        for (a, b, score_a, score_b) in bouts {
+            tableau
+                .update_score(
+                    FencerScore::new(fencers[a - 1].clone(), score_a, Cards::default()),
+                    FencerScore::new(fencers[b - 1].clone(), score_b, Cards::default()),
+                )
+                .unwrap();
+        }
+        assert!(!tableau.is_finished());
+        assert!(tableau.placements().is_err());
+
+        tableau
+            .update_score(
+                FencerScore::new(fencers[1].clone(), 15, Cards::default()),
+                FencerScore::new(fencers[0].clone(), 13, Cards::default()),
+            )
+            .unwrap();
+
+        assert_eq!(tableau.get_winner(), Some(&fencers[1]));
+        let placements: Vec<String> = tableau
+            .placements()
+            .unwrap()
+            .iter()
+            .map(|(fencer, place)| format!("{place} {}", fencer.get_fullname()))
+            .collect();
+        assert_eq!(
+            placements,
+            vec![
+                "1 Fencer2",
+                "2 Fencer1",
+                "3T Fencer3",
+                "3T Fencer5",
+                "5 Fencer4",
+                "6 Fencer6"
+            ]
+        );
+    }
+
+    #[test]
+    fn changing_result_resets_later_tables() {
+        let fencers = fencers(4);
+        let mut tableau = Tableau::new(IndexSet::from_iter(fencers.clone())).unwrap();
+
+        tableau
+            .update_score(
+                FencerScore::new(fencers[0].clone(), 15, Cards::default()),
+                FencerScore::new(fencers[3].clone(), 3, Cards::default()),
+            )
+            .unwrap();
+        tableau
+            .update_score(
+                FencerScore::new(fencers[1].clone(), 15, Cards::default()),
+                FencerScore::new(fencers[2].clone(), 3, Cards::default()),
+            )
+            .unwrap();
+        tableau
+            .update_score(
+                FencerScore::new(fencers[0].clone(), 15, Cards::default()),
+                FencerScore::new(fencers[1].clone(), 3, Cards::default()),
+            )
+            .unwrap();
+        assert_eq!(tableau.get_winner(), Some(&fencers[0]));
+
+        tableau
+            .update_score(
+                FencerScore::new(fencers[0].clone(), 10, Cards::default()),
+                FencerScore::new(fencers[3].clone(), 15, Cards::default()),
+            )
+            .unwrap();
+        assert_eq!(tableau.get_winner(), None);
+        let final_bout = tableau.get_table(2).unwrap().get_bout(0).unwrap();
+        assert_eq!(final_bout.get_fencers(), (&fencers[3], &fencers[1]));
+        assert_eq!(final_bout.get_scores(), None);
+    }
+
+    #[test]
+    fn seeded_from_pool_results() {
+        // Fencer4 wins everything, then Fencer3, Fencer2, Fencer1.
+        let results = pool_with_scores(&fencers(4), &every_bout(4, |_, _| (2, 5)))
+            .finish()
+            .unwrap();
+        let tableau = Tableau::from_pool_results(&[results]).unwrap();
+
+        let seeding: Vec<String> = tableau
+            .get_seeding()
+            .iter()
+            .map(|fencer| fencer.get_fullname())
+            .collect();
+        assert_eq!(seeding, vec!["Fencer4", "Fencer3", "Fencer2", "Fencer1"]);
+    }
+}
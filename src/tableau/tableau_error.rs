@@ -0,0 +1,43 @@
+use std::{error::Error, fmt::Display};
+
+use crate::{bout::VersusError, pools::PoolSheetError};
+
+#[derive(Debug, Clone)]
+pub enum TableauError {
+    NotEnoughFencers,
+    InvalidBout,
+    NoBoutFound,
+    NoTableFound,
+    TableauNotComplete,
+    BoutError(PoolSheetError),
+}
+
+impl Display for TableauError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use TableauError::*;
+        match self {
+            NotEnoughFencers => write!(f, "a tableau needs at least 2 fencers"),
+            InvalidBout => write!(f, "the requested bout is invalid"),
+            NoBoutFound => write!(f, "this bout does not exist in this tableau"),
+            NoTableFound => write!(f, "this tableau does not have a table of that size"),
+            TableauNotComplete => write!(f, "the tableau has bouts that have not been fenced"),
+            BoutError(err) => write!(f, "error updating the bout: {err}"),
+        }
+    }
+}
+
+impl Error for TableauError {}
+
+impl From<VersusError> for TableauError {
+    fn from(value: VersusError) -> Self {
+        match value {
+            VersusError::SameFencer => TableauError::InvalidBout,
+        }
+    }
+}
+
+impl From<PoolSheetError> for TableauError {
+    fn from(value: PoolSheetError) -> Self {
+        TableauError::BoutError(value)
+    }
+}
@@ -0,0 +1,140 @@
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::fencer::{Fencer, Identity};
+
+mod relay;
+pub use relay::{RelayLeg, TeamMatch, TeamScore, RELAY_ORDER};
+mod team_error;
+pub use team_error::TeamError;
+
+/// Teams are fencers too, so they use the same pools and tableaus.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Team<T: Fencer> {
+    #[serde(flatten)]
+    identity: Identity,
+    starters: [T; 3],
+    alternate: Option<T>,
+}
+
+impl<T: Fencer> Team<T> {
+    /// Starters are in relay order.
+    pub fn new(
+        id: impl ToString,
+        name: impl ToString,
+        starters: [T; 3],
+        alternate: Option<T>,
+    ) -> Result<Team<T>, TeamError> {
+        let team = Team {
+            identity: Identity::new(id, name),
+            starters,
+            alternate,
+        };
+
+        let mut ids: Vec<T::Id> = team.members().map(|fencer| fencer.get_id()).collect();
+        let count = ids.len();
+        ids.sort();
+        ids.dedup();
+        if ids.len() != count {
+            return Err(TeamError::DuplicateFencer);
+        }
+        Ok(team)
+    }
+
+    pub fn starters(&self) -> &[T; 3] {
+        &self.starters
+    }
+
+    pub fn alternate(&self) -> Option<&T> {
+        self.alternate.as_ref()
+    }
+
+    /// The starters followed by the alternate.
+    pub fn members(&self) -> impl Iterator<Item = &T> {
+        self.starters.iter().chain(self.alternate.iter())
+    }
+
+    pub fn contains(&self, fencer: &T) -> bool {
+        self.members()
+            .any(|member| member.get_id() == fencer.get_id())
+    }
+}
+
+impl<T: Fencer> Fencer for Team<T> {
+    type Id = String;
+
+    fn get_id(&self) -> String {
+        self.identity.id().to_string()
+    }
+
+    fn get_fullname(&self) -> String {
+        self.identity.name().to_string()
+    }
+}
+
+impl<T: Fencer> Hash for Team<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.identity.hash(state)
+    }
+}
+
+impl<T: Fencer> PartialEq for Team<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.identity == other.identity
+    }
+}
+impl<T: Fencer> Eq for Team<T> {}
+
+impl<T: Fencer> PartialOrd for Team<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Fencer> Ord for Team<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.identity.cmp(&other.identity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fencer::{Fencer, SimpleFencer};
+
+    use super::{Team, TeamError};
+
+    #[test]
+    fn team_members() {
+        let team = Team::new(
+            "1",
+            "Chargers",
+            [
+                SimpleFencer::new("A1", "A1"),
+                SimpleFencer::new("A2", "A2"),
+                SimpleFencer::new("A3", "A3"),
+            ],
+            Some(SimpleFencer::new("A4", "A4")),
+        )
+        .unwrap();
+        assert_eq!(team.get_id(), "1");
+        assert_eq!(team.get_fullname(), "Chargers");
+        assert_eq!(team.members().count(), 4);
+        assert!(team.contains(&SimpleFencer::new("A4", "A4")));
+
+        let duplicate = Team::new(
+            "1",
+            "Chargers",
+            [
+                SimpleFencer::new("A1", "A1"),
+                SimpleFencer::new("A2", "A2"),
+                SimpleFencer::new("A3", "A3"),
+            ],
+            Some(SimpleFencer::new("A1", "A1")),
+        );
+        assert_eq!(duplicate.unwrap_err(), TeamError::DuplicateFencer);
+    }
+}
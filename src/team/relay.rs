@@ -0,0 +1,353 @@
+use std::{cmp::Ordering, fmt::Debug, rc::Rc};
+
+use crate::{
+    bout::{BoutEventKind, BoutFormat, FencerScore, FencerVs, TuplePos},
+    cards::Cards,
+    fencer::Fencer,
+    tableau::{Tableau, TableauError},
+};
+
+use super::{Team, TeamError};
+
+/// Positions 1 to 3 are the first team, 4 to 6 the second.
+pub const RELAY_ORDER: [(usize, usize); 9] = [
+    (3, 6),
+    (1, 5),
+    (2, 4),
+    (1, 6),
+    (3, 4),
+    (2, 5),
+    (1, 4),
+    (2, 6),
+    (3, 5),
+];
+
+pub type TeamScore<T> = FencerScore<Team<T>, Rc<Team<T>>>;
+
+/// A leg of the relay that has been fenced.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelayLeg<T: Fencer> {
+    fencers: (Rc<T>, Rc<T>),
+    scores: (u8, u8),
+    ended_on_time: bool,
+}
+
+impl<T: Fencer> RelayLeg<T> {
+    pub fn fencers(&self) -> (&T, &T) {
+        (&self.fencers.0, &self.fencers.1)
+    }
+
+    /// The match score at the end of the leg, not the touches scored in it.
+    pub fn scores(&self) -> (u8, u8) {
+        self.scores
+    }
+
+    pub fn ended_on_time(&self) -> bool {
+        self.ended_on_time
+    }
+}
+
+/// A relay to 45, each leg goes on to the next multiple of 5.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeamMatch<T: Fencer> {
+    teams: (Rc<Team<T>>, Rc<Team<T>>),
+    lineups: ([Rc<T>; 3], [Rc<T>; 3]),
+    substituted: (bool, bool),
+    legs: Vec<RelayLeg<T>>,
+    priority: TuplePos,
+    format: BoutFormat,
+}
+
+impl<T: Fencer> TeamMatch<T> {
+    /// Creates a match with each team's starters in the order they were listed on the team.
+    pub fn new(team_a: Rc<Team<T>>, team_b: Rc<Team<T>>) -> Result<TeamMatch<T>, TeamError> {
+        if team_a.get_id() == team_b.get_id() {
+            return Err(TeamError::SameTeam);
+        }
+        let lineup = |team: &Team<T>| team.starters().clone().map(Rc::new);
+        Ok(TeamMatch {
+            lineups: (lineup(&team_a), lineup(&team_b)),
+            teams: (team_a, team_b),
+            substituted: (false, false),
+            legs: Vec::with_capacity(RELAY_ORDER.len()),
+            priority: TuplePos::None,
+            format: BoutFormat::team_relay(),
+        })
+    }
+
+    pub fn get_teams(&self) -> (&Team<T>, &Team<T>) {
+        (&self.teams.0, &self.teams.1)
+    }
+
+    pub fn get_format(&self) -> &BoutFormat {
+        &self.format
+    }
+
+    pub fn legs(&self) -> &[RelayLeg<T>] {
+        &self.legs
+    }
+
+    /// The score a leg is fenced to, legs are numbered from 0.
+    pub fn leg_target(leg: usize) -> u8 {
+        5 * (leg as u8 + 1)
+    }
+
+    /// The fencers of the next leg with the current lineups, `None` once the match is over.
+    pub fn next_bout(&self) -> Option<(&T, &T)> {
+        if self.is_finished() {
+            return None;
+        }
+        let (a, b) = RELAY_ORDER[self.legs.len()];
+        Some((&self.lineups.0[a - 1], &self.lineups.1[b - 4]))
+    }
+
+    /// The current match score.
+    pub fn scores(&self) -> (u8, u8) {
+        self.legs.last().map_or((0, 0), |leg| leg.scores)
+    }
+
+    /// Records the score at the end of the next leg.
+    pub fn record_leg(&mut self, score_a: u8, score_b: u8) -> Result<(), TeamError> {
+        if self.is_finished() {
+            return Err(TeamError::MatchFinished);
+        }
+        let (a, b) = RELAY_ORDER[self.legs.len()];
+        let fencers = (self.lineups.0[a - 1].clone(), self.lineups.1[b - 4].clone());
+
+        let target = TeamMatch::<T>::leg_target(self.legs.len());
+        let (previous_a, previous_b) = self.scores();
+        if score_a < previous_a
+            || score_b < previous_b
+            || score_a > target
+            || score_b > target
+            || (score_a == target && score_b == target)
+        {
+            return Err(TeamError::InvalidScore);
+        }
+
+        let last_leg = self.legs.len() + 1 == RELAY_ORDER.len();
+        if last_leg && score_a == score_b && self.priority == TuplePos::None {
+            return Err(TeamError::NoPriority);
+        }
+
+        self.legs.push(RelayLeg {
+            fencers,
+            scores: (score_a, score_b),
+            ended_on_time: score_a != target && score_b != target,
+        });
+        Ok(())
+    }
+
+    /// Removes the last leg that was recorded.
+    pub fn undo_leg(&mut self) -> Option<RelayLeg<T>> {
+        self.legs.pop()
+    }
+
+    /// Each team can substitute once a match.
+    pub fn substitute(&mut self, team: &Team<T>, out: &T) -> Result<(), TeamError> {
+        if self.is_finished() {
+            return Err(TeamError::MatchFinished);
+        }
+        let (team, lineup, substituted) = match self.side(team)? {
+            TuplePos::First => (&self.teams.0, &mut self.lineups.0, &mut self.substituted.0),
+            _ => (&self.teams.1, &mut self.lineups.1, &mut self.substituted.1),
+        };
+        if *substituted {
+            return Err(TeamError::SubstitutionUsed);
+        }
+        let alternate = team.alternate().ok_or(TeamError::NoAlternate)?;
+        let position = lineup
+            .iter()
+            .position(|fencer| fencer.get_id() == out.get_id())
+            .ok_or(TeamError::NotOnTeam)?;
+
+        lineup[position] = Rc::new(alternate.clone());
+        *substituted = true;
+        Ok(())
+    }
+
+    /// Gives priority to `team` for the last minute of a tied match.
+    pub fn set_priority(&mut self, team: &Team<T>) -> Result<(), TeamError> {
+        self.priority = self.side(team)?;
+        Ok(())
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.legs.len() == RELAY_ORDER.len()
+    }
+
+    pub fn ended_on_time(&self) -> bool {
+        self.is_finished() && self.legs.last().is_some_and(|leg| leg.ended_on_time)
+    }
+
+    pub fn get_winner(&self) -> Option<&Team<T>> {
+        if !self.is_finished() {
+            return None;
+        }
+        let (score_a, score_b) = self.scores();
+        match (score_a.cmp(&score_b), self.priority) {
+            (Ordering::Greater, _) | (Ordering::Equal, TuplePos::First) => Some(&self.teams.0),
+            (Ordering::Less, _) | (Ordering::Equal, TuplePos::Second) => Some(&self.teams.1),
+            (Ordering::Equal, TuplePos::None) => None,
+        }
+    }
+
+    /// Touches scored and received by a fencer over the legs they fenced.
+    pub fn fencer_touches(&self, fencer: &T) -> (u8, u8) {
+        let mut previous = (0, 0);
+        let mut touches = (0, 0);
+        for leg in self.legs.iter() {
+            let scored = (leg.scores.0 - previous.0, leg.scores.1 - previous.1);
+            if leg.fencers.0.get_id() == fencer.get_id() {
+                touches.0 += scored.0;
+                touches.1 += scored.1;
+            } else if leg.fencers.1.get_id() == fencer.get_id() {
+                touches.0 += scored.1;
+                touches.1 += scored.0;
+            }
+            previous = leg.scores;
+        }
+        touches
+    }
+
+    /// The final score of each team, to enter into a pool or tableau of teams.
+    pub fn final_scores(&self) -> Option<(TeamScore<T>, TeamScore<T>)> {
+        if !self.is_finished() {
+            return None;
+        }
+        let (score_a, score_b) = self.scores();
+        Some((
+            FencerScore::new(self.teams.0.clone(), score_a, Cards::default()),
+            FencerScore::new(self.teams.1.clone(), score_b, Cards::default()),
+        ))
+    }
+
+    fn side(&self, team: &Team<T>) -> Result<TuplePos, TeamError> {
+        if team.get_id() == self.teams.0.get_id() {
+            Ok(TuplePos::First)
+        } else if team.get_id() == self.teams.1.get_id() {
+            Ok(TuplePos::Second)
+        } else {
+            Err(TeamError::NotOnTeam)
+        }
+    }
+}
+
+impl<T: Fencer + Debug> TeamMatch<T> {
+    /// The tableau should use `BoutFormat::team_relay`.
+    pub fn record_in_tableau(&self, tableau: &mut Tableau<Team<T>>) -> Result<(), TableauError> {
+        let (score_a, score_b) = self.final_scores().ok_or(TableauError::InvalidBout)?;
+
+        if score_a.score == score_b.score {
+            let vs = FencerVs::new(self.teams.0.clone(), self.teams.1.clone())?;
+            let winner = self.get_winner().ok_or(TableauError::InvalidBout)?;
+            let (first, _) = tableau.get_bout(&vs)?.get_fencers();
+            let fencer = if first.get_id() == winner.get_id() {
+                TuplePos::First
+            } else {
+                TuplePos::Second
+            };
+            tableau.record_event(&vs, BoutEventKind::PrioritySet(fencer))?;
+        }
+
+        if self.ended_on_time() {
+            tableau.update_score_on_time(score_a, score_b)
+        } else {
+            tableau.update_score(score_a, score_b)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use indexmap::IndexSet;
+
+    use crate::{
+        bout::BoutFormat,
+        fencer::{Fencer, SimpleFencer},
+        tableau::Tableau,
+    };
+
+    use super::{Team, TeamError, TeamMatch};
+
+    fn team(name: &str) -> Team<SimpleFencer> {
+        let fencer =
+            |number: usize| SimpleFencer::new(format!("{name}{number}"), format!("{name}{number}"));
+        Team::new(
+            name,
+            name,
+            [fencer(1), fencer(2), fencer(3)],
+            Some(fencer(4)),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn relay_to_45() {
+        let mut team_match = TeamMatch::new(Rc::new(team("A")), Rc::new(team("B"))).unwrap();
+        assert_eq!(
+            team_match
+                .next_bout()
+                .map(|(a, b)| (a.get_fullname(), b.get_fullname())),
+            Some(("A3".to_string(), "B3".to_string()))
+        );
+
+        team_match.record_leg(5, 3).unwrap();
+        // The next leg can't take a score back or go past 10.
+        assert_eq!(team_match.record_leg(4, 5), Err(TeamError::InvalidScore));
+        assert_eq!(team_match.record_leg(11, 5), Err(TeamError::InvalidScore));
+        team_match.record_leg(8, 10).unwrap();
+
+        team_match
+            .substitute(&team("A"), &SimpleFencer::new("A2", "A2"))
+            .unwrap();
+        assert_eq!(
+            team_match.substitute(&team("A"), &SimpleFencer::new("A1", "A1")),
+            Err(TeamError::SubstitutionUsed)
+        );
+        assert_eq!(
+            team_match.next_bout().unwrap().0.get_fullname(),
+            "A4".to_string()
+        );
+
+        for (score_a, score_b) in [(12, 15), (20, 16), (24, 25), (30, 27), (35, 33), (40, 38)] {
+            team_match.record_leg(score_a, score_b).unwrap();
+        }
+        assert!(team_match.get_winner().is_none());
+        team_match.record_leg(45, 41).unwrap();
+        assert!(team_match.is_finished());
+        assert_eq!(team_match.get_winner(), Some(&team("A")));
+        assert_eq!(team_match.record_leg(45, 42), Err(TeamError::MatchFinished));
+
+        // A3 fenced the first, fifth and ninth legs.
+        assert_eq!(
+            team_match.fencer_touches(&SimpleFencer::new("A3", "A3")),
+            (5 + 4 + 5, 3 + 9 + 3)
+        );
+    }
+
+    #[test]
+    fn team_match_feeds_tableau() {
+        let teams = [team("A"), team("B"), team("C")];
+        let mut tableau = Tableau::new(IndexSet::from_iter(teams.clone())).unwrap();
+        tableau.set_format(BoutFormat::team_relay());
+
+        // Seed 1 has a bye, 2 and 3 fence the semifinal.
+        let mut team_match =
+            TeamMatch::new(Rc::new(teams[2].clone()), Rc::new(teams[1].clone())).unwrap();
+        for leg in 0..8 {
+            let score = TeamMatch::<SimpleFencer>::leg_target(leg);
+            team_match.record_leg(score, score - 1).unwrap();
+        }
+        assert_eq!(team_match.record_leg(42, 42), Err(TeamError::NoPriority));
+        team_match.set_priority(&teams[1]).unwrap();
+        team_match.record_leg(42, 42).unwrap();
+        assert!(team_match.ended_on_time());
+
+        team_match.record_in_tableau(&mut tableau).unwrap();
+        let final_bout = tableau.get_table(2).unwrap().get_bout(0).unwrap();
+        assert_eq!(final_bout.get_fencers().1, &teams[1]);
+    }
+}
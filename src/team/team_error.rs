@@ -0,0 +1,32 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TeamError {
+    /// A fencer was listed more than once on a team.
+    DuplicateFencer,
+    SameTeam,
+    NotOnTeam,
+    NoAlternate,
+    SubstitutionUsed,
+    InvalidScore,
+    MatchFinished,
+    NoPriority,
+}
+
+impl Display for TeamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use TeamError::*;
+        match self {
+            DuplicateFencer => write!(f, "a fencer can only be on a team once"),
+            SameTeam => write!(f, "a team can't fence itself"),
+            NotOnTeam => write!(f, "this fencer or team is not in this match"),
+            NoAlternate => write!(f, "the team does not have an alternate"),
+            SubstitutionUsed => write!(f, "the team has already made its substitution"),
+            InvalidScore => write!(f, "the score is not possible for this leg of the relay"),
+            MatchFinished => write!(f, "the match has already finished"),
+            NoPriority => write!(f, "the match ended tied, priority has to be set"),
+        }
+    }
+}
+
+impl Error for TeamError {}
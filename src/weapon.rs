@@ -0,0 +1,36 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Weapon {
+    Foil,
+    Epee,
+    Sabre,
+}
+
+impl Weapon {
+    /// Only epee scores double touches.
+    pub fn allows_double_touch(&self) -> bool {
+        matches!(self, Weapon::Epee)
+    }
+
+    pub fn uses_right_of_way(&self) -> bool {
+        matches!(self, Weapon::Foil | Weapon::Sabre)
+    }
+
+    /// Sabre bouts are too quick for passivity to apply.
+    pub fn has_passivity(&self) -> bool {
+        !matches!(self, Weapon::Sabre)
+    }
+}
+
+impl Display for Weapon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Weapon::Foil => write!(f, "Foil"),
+            Weapon::Epee => write!(f, "Epee"),
+            Weapon::Sabre => write!(f, "Sabre"),
+        }
+    }
+}